@@ -0,0 +1,52 @@
+/* Imports */
+use std::fmt::Write;
+use crate::neural_network::network::NeatNetwork;
+use super::layout::{node_color, node_label, weight_color, weight_width, NetworkLayout};
+
+impl NeatNetwork {
+    /// Renders the topology of this network in the Graphviz DOT
+    /// language, e.g `dot -Tpng network.dot -o network.png`.
+    /// 
+    /// Inputs (and the bias node) share the first rank, outputs
+    /// the last and hidden nodes are ranked by their depth. Edge
+    /// thickness and colour follow the weight (blue positive, red
+    /// negative) and disabled genes are dashed.
+    pub fn to_dot(&self) -> String {
+        let layout = NetworkLayout::new(self);
+        let bias_index = self.input_size() + self.output_size();
+        let mut dot = String::new();
+
+        // Writing to a String can't fail
+        writeln!(dot, "digraph NeatNetwork {{").unwrap();
+        writeln!(dot, "    rankdir=LR;").unwrap();
+        writeln!(dot, "    splines=true;").unwrap();
+        writeln!(dot, "    node [shape=circle, style=filled, fontname=\"Helvetica\", fontsize=10];").unwrap();
+        writeln!(dot, "    edge [arrowsize=0.6];").unwrap();
+
+        for rank in layout.ranks() {
+            if rank.is_empty() { continue; }
+            write!(dot, "    {{ rank=same;").unwrap();
+            for &index in rank {
+                let shape = if index == bias_index { ", shape=square" } else { "" };
+                write!(
+                    dot, " n{index} [label=\"{}\", fillcolor=\"{}\"{shape}];",
+                    node_label(self, index), node_color(self, index)
+                ).unwrap();
+            }
+            writeln!(dot, " }}").unwrap();
+        }
+
+        for conn in self.get_genes() {
+            let style = if conn.enabled() { "solid" } else { "dashed" };
+            writeln!(
+                dot, "    n{} -> n{} [penwidth={:.2}, color=\"{}\", style={style}, tooltip=\"{:.3} (#{})\"];",
+                conn.node_in(), conn.node_out(),
+                weight_width(conn.weight()), weight_color(conn.weight()),
+                conn.weight(), conn.innovation_number()
+            ).unwrap();
+        }
+
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
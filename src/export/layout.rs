@@ -0,0 +1,136 @@
+/* Imports */
+use std::collections::VecDeque;
+use crate::neural_network::{network::NeatNetwork, node_gene::NodeGeneType};
+
+/* Constants */
+/// Weights with an absolute value above this are drawn
+/// with the thickest stroke and the most saturated colour
+pub const MAX_DRAWN_WEIGHT: f32 = 3.0;
+
+/// The layout of a network shared by every headless renderer
+/// (DOT, SVG) so that diagrams look the same no matter which
+/// format they were exported to.
+/// 
+/// Nodes are placed in ranks (columns). The first rank contains
+/// the inputs and the bias node, the last rank the outputs and
+/// every hidden node is placed at its longest path from the
+/// first rank.
+pub struct NetworkLayout {
+    /// Node indexes grouped by rank, from inputs to outputs
+    ranks: Vec<Vec<usize>>,
+
+    /// (x, y) for every node gene, both between 0 and 1
+    positions: Vec<(f32, f32)>,
+}
+
+impl NetworkLayout {
+    pub fn new(network: &NeatNetwork) -> Self {
+        let node_genes = network.node_genes();
+        let num_nodes = node_genes.len();
+        let bias_index = network.input_size() + network.output_size();
+
+        // Longest path from any node without incoming connections,
+        // disabled connections included so they still point forward
+        let mut in_degree = vec![0; num_nodes];
+        let mut adj_list = vec![vec![]; num_nodes];
+        for conn in network.get_genes() {
+            adj_list[conn.node_in()].push(conn.node_out());
+            in_degree[conn.node_out()] += 1;
+        }
+
+        let mut depth = vec![0; num_nodes];
+        let mut queue: VecDeque<usize> = (0..num_nodes).filter(|&i| in_degree[i] == 0).collect();
+        while let Some(node) = queue.pop_front() {
+            for &neighbor in &adj_list[node] {
+                depth[neighbor] = depth[neighbor].max(depth[node] + 1);
+                in_degree[neighbor] -= 1;
+                if in_degree[neighbor] == 0 {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        // Hidden nodes always sit between the inputs and outputs
+        let mut hidden_ranks = 0;
+        for (index, node) in node_genes.iter().enumerate() {
+            if index != bias_index && node.node_type() == NodeGeneType::Regular {
+                depth[index] = depth[index].max(1);
+                hidden_ranks = hidden_ranks.max(depth[index]);
+            }
+        }
+
+        let output_rank = hidden_ranks + 1;
+        let mut ranks = vec![Vec::new(); output_rank + 1];
+        for (index, node) in node_genes.iter().enumerate() {
+            let rank = match node.node_type() {
+                _ if index == bias_index => 0,
+                NodeGeneType::Input => 0,
+                NodeGeneType::Output => output_rank,
+                NodeGeneType::Regular => depth[index],
+            };
+            ranks[rank].push(index);
+        }
+
+        // The bias node is always drawn last in the input rank
+        ranks[0].sort_by_key(|&index| index == bias_index);
+
+        let mut positions = vec![(0.0, 0.0); num_nodes];
+        for (rank_index, rank) in ranks.iter().enumerate() {
+            let x = rank_index as f32 / output_rank as f32;
+            for (i, &node_index) in rank.iter().enumerate() {
+                let y = (i as f32 + 0.5) / rank.len() as f32;
+                positions[node_index] = (x, y);
+            }
+        }
+
+        Self { ranks, positions }
+    }
+
+    /// Node indexes grouped by rank. The first rank are the
+    /// inputs and the bias, the last rank are the outputs.
+    pub fn ranks(&self) -> &Vec<Vec<usize>> { &self.ranks }
+
+    /// (x, y) of some node, both between 0 and 1
+    pub fn position(&self, index: usize) -> (f32, f32) { self.positions[index] }
+}
+
+/// Stroke width of a connection, grows with the absolute weight
+pub fn weight_width(weight: f32) -> f32 {
+    0.5 + weight.abs().min(MAX_DRAWN_WEIGHT) / MAX_DRAWN_WEIGHT * 3.5
+}
+
+/// Hex colour of a connection. Positive weights fade from grey to
+/// blue and negative ones from grey to red.
+pub fn weight_color(weight: f32) -> String {
+    let t = weight.abs().min(MAX_DRAWN_WEIGHT) / MAX_DRAWN_WEIGHT;
+    let target = if weight >= 0.0 { (33., 102., 172.) } else { (178., 24., 43.) };
+    let lerp = |from: f32, to: f32| (from + (to - from) * t).round() as u8;
+
+    format!("#{:02x}{:02x}{:02x}", lerp(190., target.0), lerp(190., target.1), lerp(190., target.2))
+}
+
+/// Fill colour of a node gene
+pub fn node_color(network: &NeatNetwork, index: usize) -> &'static str {
+    if index == network.input_size() + network.output_size() {
+        return "#f4d35e";
+    }
+
+    match network.node_genes()[index].node_type() {
+        NodeGeneType::Input => "#ffffff",
+        NodeGeneType::Regular => "#d9d9d9",
+        NodeGeneType::Output => "#6fa8dc",
+    }
+}
+
+/// Short label of a node gene, e.g `I0`, `H7`, `O3` or `B`
+pub fn node_label(network: &NeatNetwork, index: usize) -> String {
+    if index == network.input_size() + network.output_size() {
+        return "B".to_string();
+    }
+
+    match network.node_genes()[index].node_type() {
+        NodeGeneType::Input => format!("I{index}"),
+        NodeGeneType::Regular => format!("H{index}"),
+        NodeGeneType::Output => format!("O{index}"),
+    }
+}
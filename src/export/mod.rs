@@ -0,0 +1,5 @@
+pub mod layout;
+pub mod dot;
+pub mod svg;
+pub mod onnx;
+pub mod rust_source;
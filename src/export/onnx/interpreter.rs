@@ -0,0 +1,110 @@
+/* Imports */
+use std::collections::HashMap;
+use super::{OnnxAttributeValue, OnnxModel, OnnxNode, OnnxTensorData};
+
+/// A dense float tensor in row-major order
+#[derive(Clone, Debug)]
+struct Tensor {
+    dims: Vec<usize>,
+    data: Vec<f32>,
+}
+
+impl OnnxModel {
+    /// Runs the graph on a single input vector using a small
+    /// pure-Rust interpreter. Only supports the operators which
+    /// `NeatNetwork::to_onnx` emits, and is meant for checking
+    /// exported models rather than for speed.
+    /// 
+    /// Returns None if the graph uses an unsupported operator
+    /// or references a value which doesn't exist.
+    pub fn evaluate(&self, input: &[f32]) -> Option<Vec<f32>> {
+        let graph = &self.graph;
+        let mut values: HashMap<&str, Tensor> = HashMap::new();
+
+        for tensor in &graph.initializers {
+            let data = match &tensor.data {
+                OnnxTensorData::Float(data) => data.clone(),
+                OnnxTensorData::Int64(data) => data.iter().map(|&i| i as f32).collect(),
+            };
+            let dims = tensor.dims.iter().map(|&d| d as usize).collect();
+            values.insert(&tensor.name, Tensor { dims, data });
+        }
+        values.insert(&graph.inputs.first()?.name, Tensor { dims: vec![input.len()], data: input.to_vec() });
+
+        for node in &graph.nodes {
+            let inputs = node.inputs.iter()
+                .map(|name| values.get(name.as_str()))
+                .collect::<Option<Vec<&Tensor>>>()?;
+            let output = run_operator(node, &inputs)?;
+            values.insert(node.outputs.first()?, output);
+        }
+
+        values.remove(graph.outputs.first()?.name.as_str()).map(|t| t.data)
+    }
+}
+
+fn run_operator(node: &OnnxNode, inputs: &[&Tensor]) -> Option<Tensor> {
+    let unary = |f: &dyn Fn(f32) -> f32| -> Option<Tensor> {
+        let input = inputs.first()?;
+        Some(Tensor { dims: input.dims.clone(), data: input.data.iter().map(|&x| f(x)).collect() })
+    };
+
+    match node.op_type.as_str() {
+        "Identity" => unary(&|x| x),
+        "Relu" => unary(&|x| x.max(0.0)),
+        "Sigmoid" => unary(&|x| 1.0 / (1.0 + f32::exp(-x))),
+        "Tanh" => unary(&|x| x.tanh()),
+        "Sin" => unary(&|x| x.sin()),
+        "Neg" => unary(&|x| -x),
+        "Exp" => unary(&|x| x.exp()),
+        "LeakyRelu" => {
+            let alpha = float_attribute(node, "alpha").unwrap_or(0.01);
+            unary(&|x| if x > 0.0 { x } else { alpha * x })
+        },
+        "Softmax" => {
+            // Only 1-D tensors are ever softmaxed
+            let input = inputs.first()?;
+            let sum: f32 = input.data.iter().map(|x| x.exp()).sum();
+            Some(Tensor { dims: input.dims.clone(), data: input.data.iter().map(|x| x.exp() / sum).collect() })
+        },
+        "Gather" => {
+            let (data, indices) = (inputs.first()?, inputs.get(1)?);
+            let gathered = indices.data.iter()
+                .map(|&i| data.data.get(i as usize).copied())
+                .collect::<Option<Vec<f32>>>()?;
+            Some(Tensor { dims: indices.dims.clone(), data: gathered })
+        },
+        "Concat" => {
+            let data: Vec<f32> = inputs.iter().flat_map(|t| t.data.iter().copied()).collect();
+            Some(Tensor { dims: vec![data.len()], data })
+        },
+        "MatMul" => {
+            // [k] x [k, n] -> [n]
+            let (lhs, rhs) = (inputs.first()?, inputs.get(1)?);
+            let (k, n) = match rhs.dims.as_slice() { &[k, n] => (k, n), _ => return None };
+            if lhs.data.len() != k { return None }
+            let data = (0..n).map(|col| {
+                (0..k).map(|row| lhs.data[row] * rhs.data[row * n + col]).sum()
+            }).collect();
+            Some(Tensor { dims: vec![n], data })
+        },
+        "Add" => elementwise(inputs, |a, b| a + b),
+        "Mul" => elementwise(inputs, |a, b| a * b),
+        _ => None,
+    }
+}
+
+/// Binary operator on two tensors of the same length
+fn elementwise(inputs: &[&Tensor], f: impl Fn(f32, f32) -> f32) -> Option<Tensor> {
+    let (lhs, rhs) = (inputs.first()?, inputs.get(1)?);
+    if lhs.data.len() != rhs.data.len() { return None }
+    let data = lhs.data.iter().zip(&rhs.data).map(|(&a, &b)| f(a, b)).collect();
+    Some(Tensor { dims: lhs.dims.clone(), data })
+}
+
+fn float_attribute(node: &OnnxNode, name: &str) -> Option<f32> {
+    node.attributes.iter().find(|a| a.name == name).and_then(|a| match a.value {
+        OnnxAttributeValue::Float(f) => Some(f),
+        OnnxAttributeValue::Int(_) => None,
+    })
+}
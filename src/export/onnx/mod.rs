@@ -0,0 +1,278 @@
+/* Imports */
+use std::collections::HashSet;
+use crate::neural_network::{activation::{Activation, LEAKY_RELU_NEGATIVE_SLOPE}, compiled::{CompiledNetwork, CompiledNodeKind}, network::NeatNetwork};
+
+pub mod proto;
+pub mod interpreter;
+
+/* Constants */
+pub const ONNX_IR_VERSION: i64 = 8;
+pub const ONNX_OPSET_VERSION: i64 = 13;
+const INPUT_NAME: &str = "input";
+const OUTPUT_NAME: &str = "output";
+const ZERO_NAME: &str = "zero";
+
+/// An ONNX model holding a single graph. Only the parts of the
+/// ONNX spec which are needed to describe a NEAT network are
+/// modelled here, see `proto` for the wire format.
+#[derive(Clone, PartialEq, Debug)]
+pub struct OnnxModel {
+    pub ir_version: i64,
+    pub opset_version: i64,
+    pub producer_name: String,
+    pub graph: OnnxGraph,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct OnnxGraph {
+    pub name: String,
+
+    /// Operators, already in topological order
+    pub nodes: Vec<OnnxNode>,
+
+    /// Constant tensors (weights, biases and gather indices)
+    pub initializers: Vec<OnnxTensor>,
+    pub inputs: Vec<OnnxValueInfo>,
+    pub outputs: Vec<OnnxValueInfo>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct OnnxNode {
+    pub name: String,
+    pub op_type: String,
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+    pub attributes: Vec<OnnxAttribute>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct OnnxAttribute {
+    pub name: String,
+    pub value: OnnxAttributeValue,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum OnnxAttributeValue {
+    Float(f32),
+    Int(i64),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct OnnxTensor {
+    pub name: String,
+    pub dims: Vec<i64>,
+    pub data: OnnxTensorData,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum OnnxTensorData {
+    Float(Vec<f32>),
+    Int64(Vec<i64>),
+}
+
+/// A named float tensor of a fixed shape, used for the
+/// graph inputs and outputs
+#[derive(Clone, PartialEq, Debug)]
+pub struct OnnxValueInfo {
+    pub name: String,
+    pub dims: Vec<i64>,
+}
+
+impl OnnxModel {
+    /// Serializes the model to the ONNX protobuf format
+    pub fn to_bytes(&self) -> Vec<u8> {
+        proto::encode_model(self)
+    }
+
+    /// Parses a model previously written by `to_bytes`. Returns
+    /// None if the bytes aren't valid protobuf or use ONNX
+    /// features which aren't modelled here.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        proto::decode_model(bytes)
+    }
+
+    /// Save model as a `.onnx` file
+    pub fn save(&self, path: &str) {
+        if let Err(e) = std::fs::write(path, self.to_bytes()) {
+            println!("Cant write to {path}");
+            println!("{e:?}");
+        }
+    }
+}
+
+impl NeatNetwork {
+    /// Converts this network into an ONNX graph which takes a single
+    /// float tensor `input` of shape [input_size] and returns `output`
+    /// of shape [output_size].
+    /// 
+    /// Every node is evaluated in the order of `topological_sort`:
+    /// the incoming values are gathered / concatenated into one
+    /// vector, multiplied (`MatMul`) with the incoming weights, the
+    /// bias is added and lastly the activation operator is applied.
+    /// 
+    /// Returns None if the network contains a cycle.
+    pub fn to_onnx(&self) -> Option<OnnxModel> {
+        let compiled = CompiledNetwork::new(self)?;
+        let activations = compiled.activations();
+        let mut graph = OnnxGraph {
+            name: "neat_network".to_string(),
+            nodes: Vec::new(),
+            initializers: Vec::new(),
+            inputs: vec![OnnxValueInfo { name: INPUT_NAME.to_string(), dims: vec![compiled.input_size() as i64] }],
+            outputs: vec![OnnxValueInfo { name: OUTPUT_NAME.to_string(), dims: vec![compiled.output_size() as i64] }],
+        };
+
+        // Inputs and constants are available before any other node is
+        // computed. Nodes which are read before they are computed (stale
+        // incoming indexes) read as zero, like in `calculate_output`
+        let mut computed = HashSet::new();
+        for node in compiled.nodes() {
+            let value = value_name(node.index);
+            match node.kind {
+                CompiledNodeKind::Input(n) => {
+                    let indices = format!("{value}_index");
+                    graph.initializers.push(OnnxTensor {
+                        name: indices.clone(),
+                        dims: vec![1],
+                        data: OnnxTensorData::Int64(vec![n as i64]),
+                    });
+                    graph.push_node("Gather", vec![INPUT_NAME.to_string(), indices], &value, vec![
+                        OnnxAttribute { name: "axis".to_string(), value: OnnxAttributeValue::Int(0) }
+                    ]);
+                },
+                CompiledNodeKind::Constant(constant) => {
+                    graph.initializers.push(OnnxTensor {
+                        name: value,
+                        dims: vec![1],
+                        data: OnnxTensorData::Float(vec![constant]),
+                    });
+                },
+                _ => continue,
+            }
+            computed.insert(node.index);
+        }
+
+        for node in compiled.nodes() {
+            let value = value_name(node.index);
+            match node.kind {
+                CompiledNodeKind::Input(_) | CompiledNodeKind::Constant(_) => continue,
+                CompiledNodeKind::Hidden(_) | CompiledNodeKind::Output => {
+                    let bias = format!("{value}_bias");
+                    graph.initializers.push(OnnxTensor {
+                        name: bias.clone(),
+                        dims: vec![1],
+                        data: OnnxTensorData::Float(vec![node.bias]),
+                    });
+
+                    // Without incoming connections the sum is just the bias
+                    let sum = if node.incoming.is_empty() {
+                        bias
+                    }else {
+                        let gathered = format!("{value}_gathered");
+                        let weights = format!("{value}_weights");
+                        let product = format!("{value}_product");
+                        let sum = format!("{value}_sum");
+                        let incoming = node.incoming.iter().map(|&(from, _)| {
+                            if computed.contains(&from) { value_name(from) } else { graph.zero() }
+                        }).collect();
+
+                        graph.initializers.push(OnnxTensor {
+                            name: weights.clone(),
+                            dims: vec![node.incoming.len() as i64, 1],
+                            data: OnnxTensorData::Float(node.incoming.iter().map(|&(_, w)| w).collect()),
+                        });
+                        graph.push_node("Concat", incoming, &gathered, vec![
+                            OnnxAttribute { name: "axis".to_string(), value: OnnxAttributeValue::Int(0) }
+                        ]);
+                        graph.push_node("MatMul", vec![gathered, weights], &product, Vec::new());
+                        graph.push_node("Add", vec![product, bias], &sum, Vec::new());
+                        sum
+                    };
+
+                    // Output activations are applied to all outputs at once
+                    match node.kind {
+                        CompiledNodeKind::Hidden(activation) => graph.push_activation(activation, sum, &value),
+                        _ => graph.push_node("Identity", vec![sum], &value, Vec::new()),
+                    };
+                },
+            }
+            computed.insert(node.index);
+        }
+
+        let outputs = compiled.outputs().iter().map(|&i| value_name(i)).collect();
+        graph.push_node("Concat", outputs, "output_sum", vec![
+            OnnxAttribute { name: "axis".to_string(), value: OnnxAttributeValue::Int(0) }
+        ]);
+        graph.push_activation(activations.output, "output_sum".to_string(), OUTPUT_NAME);
+
+        Some(OnnxModel {
+            ir_version: ONNX_IR_VERSION,
+            opset_version: ONNX_OPSET_VERSION,
+            producer_name: "neat_algorithm".to_string(),
+            graph,
+        })
+    }
+}
+
+impl OnnxGraph {
+    fn push_node(&mut self, op_type: &str, inputs: Vec<String>, output: &str, attributes: Vec<OnnxAttribute>) {
+        self.nodes.push(OnnxNode {
+            name: format!("{output}_{}", op_type.to_lowercase()),
+            op_type: op_type.to_string(),
+            inputs,
+            outputs: vec![output.to_string()],
+            attributes,
+        });
+    }
+
+    /// Name of a [1] shaped initializer holding 0.0, created
+    /// the first time it's needed
+    fn zero(&mut self) -> String {
+        if !self.initializers.iter().any(|t| t.name == ZERO_NAME) {
+            self.initializers.push(OnnxTensor {
+                name: ZERO_NAME.to_string(),
+                dims: vec![1],
+                data: OnnxTensorData::Float(vec![0.0]),
+            });
+        }
+        ZERO_NAME.to_string()
+    }
+
+    fn push_activation(&mut self, activation: Activation, input: String, output: &str) {
+        match activation_operator(activation) {
+            Some((op_type, attributes)) => self.push_node(op_type, vec![input], output, attributes),
+
+            // e^(-x^2)
+            None => {
+                let (squared, negated) = (format!("{output}_squared"), format!("{output}_negated"));
+                self.push_node("Mul", vec![input.clone(), input], &squared, Vec::new());
+                self.push_node("Neg", vec![squared], &negated, Vec::new());
+                self.push_node("Exp", vec![negated], output, Vec::new());
+            },
+        }
+    }
+}
+
+/// The ONNX operator (and its attributes) which matches
+/// some `Activation`. None if there is no single operator
+/// for it (Gaussian, which is written as Mul, Neg and Exp)
+pub fn activation_operator(activation: Activation) -> Option<(&'static str, Vec<OnnxAttribute>)> {
+    match activation {
+        Activation::Relu => Some(("Relu", Vec::new())),
+        Activation::Linear => Some(("Identity", Vec::new())),
+        Activation::LeakyRelu => Some(("LeakyRelu", vec![
+            OnnxAttribute { name: "alpha".to_string(), value: OnnxAttributeValue::Float(LEAKY_RELU_NEGATIVE_SLOPE) }
+        ])),
+        Activation::Sigmoid => Some(("Sigmoid", Vec::new())),
+        Activation::Softmax => Some(("Softmax", vec![
+            OnnxAttribute { name: "axis".to_string(), value: OnnxAttributeValue::Int(0) }
+        ])),
+        Activation::Tanh => Some(("Tanh", Vec::new())),
+        Activation::Sine => Some(("Sin", Vec::new())),
+        Activation::Gaussian => None,
+    }
+}
+
+fn value_name(index: usize) -> String {
+    format!("node_{index}")
+}
@@ -0,0 +1,391 @@
+// Minimal protobuf encoding / decoding of the ONNX messages in
+// `super`. Field numbers follow `onnx.proto3` from the ONNX repo.
+
+/* Imports */
+use super::{OnnxAttribute, OnnxAttributeValue, OnnxGraph, OnnxModel, OnnxNode, OnnxTensor, OnnxTensorData, OnnxValueInfo};
+
+/* Constants */
+const WIRE_VARINT: u8 = 0;
+const WIRE_FIXED64: u8 = 1;
+const WIRE_LENGTH_DELIMITED: u8 = 2;
+const WIRE_FIXED32: u8 = 5;
+
+/// `TensorProto.DataType`
+const DATA_TYPE_FLOAT: i64 = 1;
+const DATA_TYPE_INT64: i64 = 7;
+
+/// `AttributeProto.AttributeType`
+const ATTRIBUTE_TYPE_FLOAT: i64 = 1;
+const ATTRIBUTE_TYPE_INT: i64 = 2;
+
+#[derive(Default)]
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buf.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.buf.push(value as u8);
+    }
+    fn key(&mut self, field: u32, wire_type: u8) {
+        self.varint(((field as u64) << 3) | wire_type as u64);
+    }
+    fn int(&mut self, field: u32, value: i64) {
+        self.key(field, WIRE_VARINT);
+        self.varint(value as u64);
+    }
+    fn float(&mut self, field: u32, value: f32) {
+        self.key(field, WIRE_FIXED32);
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+    fn bytes(&mut self, field: u32, bytes: &[u8]) {
+        self.key(field, WIRE_LENGTH_DELIMITED);
+        self.varint(bytes.len() as u64);
+        self.buf.extend_from_slice(bytes);
+    }
+    fn string(&mut self, field: u32, value: &str) {
+        self.bytes(field, value.as_bytes());
+    }
+    fn message(&mut self, field: u32, message: Writer) {
+        self.bytes(field, &message.buf);
+    }
+    fn packed_ints(&mut self, field: u32, values: &[i64]) {
+        let mut packed = Writer::default();
+        for &value in values { packed.varint(value as u64); }
+        self.message(field, packed);
+    }
+    fn packed_floats(&mut self, field: u32, values: &[f32]) {
+        let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        self.bytes(field, &bytes);
+    }
+}
+
+pub fn encode_model(model: &OnnxModel) -> Vec<u8> {
+    let mut w = Writer::default();
+    w.int(1, model.ir_version);
+    w.string(2, &model.producer_name);
+    w.message(7, encode_graph(&model.graph));
+
+    // Default ONNX domain
+    let mut opset = Writer::default();
+    opset.string(1, "");
+    opset.int(2, model.opset_version);
+    w.message(8, opset);
+    w.buf
+}
+
+fn encode_graph(graph: &OnnxGraph) -> Writer {
+    let mut w = Writer::default();
+    for node in &graph.nodes { w.message(1, encode_node(node)); }
+    w.string(2, &graph.name);
+    for tensor in &graph.initializers { w.message(5, encode_tensor(tensor)); }
+    for input in &graph.inputs { w.message(11, encode_value_info(input)); }
+    for output in &graph.outputs { w.message(12, encode_value_info(output)); }
+    w
+}
+
+fn encode_node(node: &OnnxNode) -> Writer {
+    let mut w = Writer::default();
+    for input in &node.inputs { w.string(1, input); }
+    for output in &node.outputs { w.string(2, output); }
+    w.string(3, &node.name);
+    w.string(4, &node.op_type);
+    for attribute in &node.attributes {
+        let mut a = Writer::default();
+        a.string(1, &attribute.name);
+        match attribute.value {
+            OnnxAttributeValue::Float(f) => { a.float(2, f); a.int(20, ATTRIBUTE_TYPE_FLOAT); },
+            OnnxAttributeValue::Int(i) => { a.int(3, i); a.int(20, ATTRIBUTE_TYPE_INT); },
+        }
+        w.message(5, a);
+    }
+    w
+}
+
+fn encode_tensor(tensor: &OnnxTensor) -> Writer {
+    let mut w = Writer::default();
+    w.packed_ints(1, &tensor.dims);
+    match &tensor.data {
+        OnnxTensorData::Float(data) => { w.int(2, DATA_TYPE_FLOAT); w.packed_floats(4, data); },
+        OnnxTensorData::Int64(data) => { w.int(2, DATA_TYPE_INT64); w.packed_ints(7, data); },
+    }
+    w.string(8, &tensor.name);
+    w
+}
+
+fn encode_value_info(info: &OnnxValueInfo) -> Writer {
+    let mut shape = Writer::default();
+    for &dim in &info.dims {
+        let mut d = Writer::default();
+        d.int(1, dim);
+        shape.message(1, d);
+    }
+
+    let mut tensor_type = Writer::default();
+    tensor_type.int(1, DATA_TYPE_FLOAT);
+    tensor_type.message(2, shape);
+
+    let mut type_proto = Writer::default();
+    type_proto.message(1, tensor_type);
+
+    let mut w = Writer::default();
+    w.string(1, &info.name);
+    w.message(2, type_proto);
+    w
+}
+
+/// A single decoded protobuf field
+enum Value<'a> {
+    Varint(u64),
+    Fixed32(u32),
+    Bytes(&'a [u8]),
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+    fn varint(&mut self) -> Option<u64> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = *self.buf.get(self.pos)?;
+            self.pos += 1;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 { return Some(value) }
+        }
+        None
+    }
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.buf.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    /// Returns the next (field number, value), Some(None) at the
+    /// end of the message and None if the bytes are malformed
+    fn field(&mut self) -> Option<Option<(u32, Value<'a>)>> {
+        if self.pos == self.buf.len() { return Some(None) }
+        let key = self.varint()?;
+        let field = (key >> 3) as u32;
+        let value = match (key & 0x7) as u8 {
+            WIRE_VARINT => Value::Varint(self.varint()?),
+            WIRE_FIXED64 => { self.take(8)?; return self.field() },
+            WIRE_LENGTH_DELIMITED => {
+                let len = self.varint()? as usize;
+                Value::Bytes(self.take(len)?)
+            },
+            WIRE_FIXED32 => Value::Fixed32(u32::from_le_bytes(self.take(4)?.try_into().ok()?)),
+            _ => return None,
+        };
+        Some(Some((field, value)))
+    }
+}
+
+/// Calls `f` for every field of the message in `bytes`
+fn for_each_field<'a>(bytes: &'a [u8], mut f: impl FnMut(u32, Value<'a>) -> Option<()>) -> Option<()> {
+    let mut reader = Reader::new(bytes);
+    while let Some((field, value)) = reader.field()? {
+        f(field, value)?;
+    }
+    Some(())
+}
+
+fn string(value: Value) -> Option<String> {
+    match value {
+        Value::Bytes(bytes) => String::from_utf8(bytes.to_vec()).ok(),
+        _ => None,
+    }
+}
+fn int(value: Value) -> Option<i64> {
+    match value {
+        Value::Varint(v) => Some(v as i64),
+        _ => None,
+    }
+}
+
+/// Repeated int64, packed or not
+fn ints(value: Value, out: &mut Vec<i64>) -> Option<()> {
+    match value {
+        Value::Varint(v) => out.push(v as i64),
+        Value::Bytes(bytes) => {
+            let mut reader = Reader::new(bytes);
+            while reader.pos < bytes.len() { out.push(reader.varint()? as i64); }
+        },
+        _ => return None,
+    }
+    Some(())
+}
+
+/// Repeated float, packed or not
+fn floats(value: Value, out: &mut Vec<f32>) -> Option<()> {
+    match value {
+        Value::Fixed32(v) => out.push(f32::from_bits(v)),
+        Value::Bytes(bytes) => {
+            if bytes.len() % 4 != 0 { return None }
+            out.extend(bytes.chunks_exact(4).map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]])));
+        },
+        _ => return None,
+    }
+    Some(())
+}
+
+pub fn decode_model(bytes: &[u8]) -> Option<OnnxModel> {
+    let mut ir_version = 0;
+    let mut opset_version = 0;
+    let mut producer_name = String::new();
+    let mut graph = None;
+
+    for_each_field(bytes, |field, value| {
+        match (field, value) {
+            (1, value) => ir_version = int(value)?,
+            (2, value) => producer_name = string(value)?,
+            (7, Value::Bytes(bytes)) => graph = Some(decode_graph(bytes)?),
+            (8, Value::Bytes(bytes)) => for_each_field(bytes, |field, value| {
+                if field == 2 { opset_version = int(value)?; }
+                Some(())
+            })?,
+            _ => {},
+        };
+        Some(())
+    })?;
+
+    Some(OnnxModel { ir_version, opset_version, producer_name, graph: graph? })
+}
+
+fn decode_graph(bytes: &[u8]) -> Option<OnnxGraph> {
+    let mut graph = OnnxGraph {
+        name: String::new(),
+        nodes: Vec::new(),
+        initializers: Vec::new(),
+        inputs: Vec::new(),
+        outputs: Vec::new(),
+    };
+
+    for_each_field(bytes, |field, value| {
+        match (field, value) {
+            (1, Value::Bytes(bytes)) => graph.nodes.push(decode_node(bytes)?),
+            (2, value) => graph.name = string(value)?,
+            (5, Value::Bytes(bytes)) => graph.initializers.push(decode_tensor(bytes)?),
+            (11, Value::Bytes(bytes)) => graph.inputs.push(decode_value_info(bytes)?),
+            (12, Value::Bytes(bytes)) => graph.outputs.push(decode_value_info(bytes)?),
+            _ => {},
+        };
+        Some(())
+    })?;
+
+    Some(graph)
+}
+
+fn decode_node(bytes: &[u8]) -> Option<OnnxNode> {
+    let mut node = OnnxNode {
+        name: String::new(),
+        op_type: String::new(),
+        inputs: Vec::new(),
+        outputs: Vec::new(),
+        attributes: Vec::new(),
+    };
+
+    for_each_field(bytes, |field, value| {
+        match (field, value) {
+            (1, value) => node.inputs.push(string(value)?),
+            (2, value) => node.outputs.push(string(value)?),
+            (3, value) => node.name = string(value)?,
+            (4, value) => node.op_type = string(value)?,
+            (5, Value::Bytes(bytes)) => node.attributes.push(decode_attribute(bytes)?),
+            _ => {},
+        };
+        Some(())
+    })?;
+
+    Some(node)
+}
+
+fn decode_attribute(bytes: &[u8]) -> Option<OnnxAttribute> {
+    let mut name = String::new();
+    let mut float = None;
+    let mut integer = None;
+    let mut attribute_type = 0;
+
+    for_each_field(bytes, |field, value| {
+        match (field, value) {
+            (1, value) => name = string(value)?,
+            (2, Value::Fixed32(bits)) => float = Some(f32::from_bits(bits)),
+            (3, value) => integer = Some(int(value)?),
+            (20, value) => attribute_type = int(value)?,
+            _ => {},
+        };
+        Some(())
+    })?;
+
+    let value = match attribute_type {
+        ATTRIBUTE_TYPE_FLOAT => OnnxAttributeValue::Float(float.unwrap_or(0.0)),
+        ATTRIBUTE_TYPE_INT => OnnxAttributeValue::Int(integer.unwrap_or(0)),
+        _ => return None,
+    };
+    Some(OnnxAttribute { name, value })
+}
+
+fn decode_tensor(bytes: &[u8]) -> Option<OnnxTensor> {
+    let mut name = String::new();
+    let mut dims = Vec::new();
+    let mut data_type = 0;
+    let mut float_data = Vec::new();
+    let mut int64_data = Vec::new();
+
+    for_each_field(bytes, |field, value| {
+        match field {
+            1 => ints(value, &mut dims)?,
+            2 => data_type = int(value)?,
+            4 => floats(value, &mut float_data)?,
+            7 => ints(value, &mut int64_data)?,
+            8 => name = string(value)?,
+            _ => {},
+        };
+        Some(())
+    })?;
+
+    let data = match data_type {
+        DATA_TYPE_FLOAT => OnnxTensorData::Float(float_data),
+        DATA_TYPE_INT64 => OnnxTensorData::Int64(int64_data),
+        _ => return None,
+    };
+    Some(OnnxTensor { name, dims, data })
+}
+
+fn decode_value_info(bytes: &[u8]) -> Option<OnnxValueInfo> {
+    let mut name = String::new();
+    let mut dims = Vec::new();
+
+    // ValueInfoProto -> TypeProto -> TypeProto.Tensor -> TensorShapeProto -> Dimension
+    for_each_field(bytes, |field, value| {
+        match (field, value) {
+            (1, value) => name = string(value)?,
+            (2, Value::Bytes(type_proto)) => for_each_field(type_proto, |field, value| {
+                let (1, Value::Bytes(tensor_type)) = (field, value) else { return Some(()) };
+                for_each_field(tensor_type, |field, value| {
+                    let (2, Value::Bytes(shape)) = (field, value) else { return Some(()) };
+                    for_each_field(shape, |field, value| {
+                        let (1, Value::Bytes(dim)) = (field, value) else { return Some(()) };
+                        for_each_field(dim, |field, value| {
+                            if field == 1 { dims.push(int(value)?); }
+                            Some(())
+                        })
+                    })
+                })
+            })?,
+            _ => {},
+        };
+        Some(())
+    })?;
+
+    Some(OnnxValueInfo { name, dims })
+}
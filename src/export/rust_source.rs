@@ -0,0 +1,121 @@
+/* Imports */
+use std::{collections::HashSet, fmt::Write};
+use crate::neural_network::{activation::{Activation, LEAKY_RELU_NEGATIVE_SLOPE}, compiled::{CompiledNetwork, CompiledNodeKind}, network::NeatNetwork};
+
+impl NeatNetwork {
+    /// Freezes this network into dependency free Rust source code,
+    /// a single `pub fn fn_name(input: &[f32; N]) -> [f32; M]` with
+    /// every weight inlined in topological order and the activations
+    /// expanded. The function returns exactly (bit for bit) what
+    /// `calculate_output` returns.
+    /// 
+    /// Returns None if the network contains a cycle.
+    pub fn to_rust_source(&self, fn_name: &str) -> Option<String> {
+        let compiled = CompiledNetwork::new(self)?;
+        let activations = compiled.activations();
+        let mut src = String::new();
+
+        // Writing to a String can't fail
+        writeln!(src, "// Generated by neat_algorithm, do not edit.").unwrap();
+        writeln!(src, "#[allow(unused_variables, clippy::all)]").unwrap();
+        writeln!(
+            src, "pub fn {fn_name}(input: &[f32; {}]) -> [f32; {}] {{",
+            compiled.input_size(), compiled.output_size()
+        ).unwrap();
+
+        // Inputs and constants exist before any other node is computed.
+        // Nodes which are read before they are computed read as zero,
+        // like in `calculate_output`
+        let mut computed = HashSet::new();
+        for node in compiled.nodes() {
+            match node.kind {
+                CompiledNodeKind::Input(n) => writeln!(src, "    let n{} = input[{n}];", node.index).unwrap(),
+                CompiledNodeKind::Constant(value) => writeln!(src, "    let n{} = {};", node.index, literal(value)).unwrap(),
+                _ => continue,
+            }
+            computed.insert(node.index);
+        }
+
+        for node in compiled.nodes() {
+            if matches!(node.kind, CompiledNodeKind::Input(_) | CompiledNodeKind::Constant(_)) { continue; }
+
+            let mut sum = literal(node.bias);
+            for &(from, weight) in &node.incoming {
+                let value = if computed.contains(&from) { format!("n{from}") } else { literal(0.0) };
+                write!(sum, " + {value} * {}", literal(weight)).unwrap();
+            }
+
+            match node.kind {
+                CompiledNodeKind::Hidden(activation) => {
+                    writeln!(src, "    let n{} = {{ let x = {sum}; {} }};", node.index, expand_activation(activation)).unwrap();
+                },
+                _ => writeln!(src, "    let n{} = {sum};", node.index).unwrap(),
+            }
+            computed.insert(node.index);
+        }
+
+        let outputs: Vec<String> = compiled.outputs().iter().map(|i| format!("n{i}")).collect();
+        match activations.output {
+            Activation::Softmax => {
+                writeln!(src, "    let mut exponent_sum = 0.0f32;").unwrap();
+                for output in &outputs {
+                    writeln!(src, "    exponent_sum += f32::exp({output});").unwrap();
+                }
+                let activated: Vec<String> = outputs.iter().map(|o| format!("f32::exp({o}) / exponent_sum")).collect();
+                writeln!(src, "    [{}]", activated.join(", ")).unwrap();
+            },
+            activation => {
+                let activated: Vec<String> = outputs.iter()
+                    .map(|o| format!("{{ let x = {o}; {} }}", expand_activation(activation)))
+                    .collect();
+                writeln!(src, "    [{}]", activated.join(", ")).unwrap();
+            },
+        }
+
+        writeln!(src, "}}").unwrap();
+        Some(src)
+    }
+}
+
+/// Reads a network saved with `NeatNetwork::save` and returns the
+/// generated module, see `NeatNetwork::to_rust_source`. Meant to be
+/// called from a build script:
+/// 
+/// ```ignore
+/// let src = generate_module("champion.bin", "champion").unwrap();
+/// std::fs::write(Path::new(&env::var("OUT_DIR").unwrap()).join("champion.rs"), src).unwrap();
+/// println!("cargo:rerun-if-changed=champion.bin");
+/// ```
+/// 
+/// and then `include!(concat!(env!("OUT_DIR"), "/champion.rs"));`
+/// 
+/// Returns None if the file can't be read or the network is cyclic.
+pub fn generate_module(network_path: &str, fn_name: &str) -> Option<String> {
+    NeatNetwork::retrieve(network_path)?.to_rust_source(fn_name)
+}
+
+/// Activation of a single value `x`, written as an expression
+fn expand_activation(activation: Activation) -> String {
+    match activation {
+        Activation::Relu => "if x > 0.0 { x } else { 0.0 }".to_string(),
+        Activation::Linear => "x".to_string(),
+        Activation::LeakyRelu => format!("if x > 0.0 {{ x }} else {{ {} * x }}", literal(LEAKY_RELU_NEGATIVE_SLOPE)),
+        Activation::Sigmoid => "1.0 / (1.0 + f32::exp(-x))".to_string(),
+        // Softmax of a single value, the sum starts at 0.0
+        Activation::Softmax => "f32::exp(x) / (0.0 + f32::exp(x))".to_string(),
+        Activation::Tanh => "f32::tanh(x)".to_string(),
+        Activation::Sine => "f32::sin(x)".to_string(),
+        Activation::Gaussian => "f32::exp(-x * x)".to_string(),
+    }
+}
+
+/// An f32 literal which parses back to exactly the same bits
+fn literal(value: f32) -> String {
+    if value.is_nan() {
+        "f32::NAN".to_string()
+    }else if value.is_infinite() {
+        if value > 0.0 { "f32::INFINITY".to_string() } else { "f32::NEG_INFINITY".to_string() }
+    }else {
+        format!("{value:?}f32")
+    }
+}
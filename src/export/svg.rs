@@ -0,0 +1,76 @@
+/* Imports */
+use std::fmt::Write;
+use crate::neural_network::network::NeatNetwork;
+use super::layout::{node_color, node_label, weight_color, weight_width, NetworkLayout};
+
+/* Constants */
+const NODE_RADIUS: f32 = 9.;
+const PADDING: f32 = 30.;
+
+impl NeatNetwork {
+    /// Renders the topology of this network as a standalone SVG
+    /// document of `width` x `height` pixels. Uses the same layout
+    /// and styling as `to_dot` but doesn't require Graphviz.
+    pub fn to_svg(&self, width: f32, height: f32) -> String {
+        let layout = NetworkLayout::new(self);
+        let bias_index = self.input_size() + self.output_size();
+        let inner_w = (width - PADDING * 2.).max(0.);
+        let inner_h = (height - PADDING * 2.).max(0.);
+        let to_pixels = |index: usize| {
+            let (x, y) = layout.position(index);
+            (PADDING + x * inner_w, PADDING + y * inner_h)
+        };
+        let mut svg = String::new();
+
+        // Writing to a String can't fail
+        writeln!(
+            svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+        ).unwrap();
+        writeln!(
+            svg, r##"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="4" markerHeight="4" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z" fill="#555555"/></marker></defs>"##
+        ).unwrap();
+        writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#).unwrap();
+
+        for conn in self.get_genes() {
+            let (x1, y1) = to_pixels(conn.node_in());
+            let (x2, y2) = to_pixels(conn.node_out());
+
+            // Stop the line at the edge of the target node so the
+            // arrow head stays visible
+            let len = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt().max(f32::EPSILON);
+            let x2 = x2 - (x2 - x1) / len * NODE_RADIUS;
+            let y2 = y2 - (y2 - y1) / len * NODE_RADIUS;
+
+            let dash = if conn.enabled() { "" } else { r#" stroke-dasharray="5,4""# };
+            writeln!(
+                svg, r#"<line x1="{x1:.2}" y1="{y1:.2}" x2="{x2:.2}" y2="{y2:.2}" stroke="{}" stroke-width="{:.2}"{dash} marker-end="url(#arrow)"><title>{:.3} (#{})</title></line>"#,
+                weight_color(conn.weight()), weight_width(conn.weight()),
+                conn.weight(), conn.innovation_number()
+            ).unwrap();
+        }
+
+        for rank in layout.ranks() {
+            for &index in rank {
+                let (x, y) = to_pixels(index);
+                let fill = node_color(self, index);
+                if index == bias_index {
+                    writeln!(
+                        svg, r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{fill}" stroke="black"/>"#,
+                        x - NODE_RADIUS, y - NODE_RADIUS, NODE_RADIUS * 2., NODE_RADIUS * 2.
+                    ).unwrap();
+                }else {
+                    writeln!(
+                        svg, r#"<circle cx="{x:.2}" cy="{y:.2}" r="{NODE_RADIUS}" fill="{fill}" stroke="black"/>"#
+                    ).unwrap();
+                }
+                writeln!(
+                    svg, r#"<text x="{x:.2}" y="{:.2}" font-family="Helvetica" font-size="10" text-anchor="middle">{}</text>"#,
+                    y - NODE_RADIUS - 4., node_label(self, index)
+                ).unwrap();
+            }
+        }
+
+        writeln!(svg, "</svg>").unwrap();
+        svg
+    }
+}
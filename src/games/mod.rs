@@ -1,3 +1,4 @@
 pub mod snake;
 pub mod pendulum_single;
 pub mod pendulum_multiple;
+pub mod tic_tac_toe;
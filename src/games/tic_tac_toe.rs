@@ -0,0 +1,123 @@
+use crate::{neural_network::network::NeatNetwork, trainer::competitive::PairwiseEvaluator};
+
+const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2], [3, 4, 5], [6, 7, 8],
+    [0, 3, 6], [1, 4, 7], [2, 5, 8],
+    [0, 4, 8], [2, 4, 6],
+];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Cell {
+    Empty,
+    X,
+    O,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Outcome {
+    Winner(Cell),
+    Draw,
+}
+
+/// Tic-tac-toe for two networks with 9 inputs and 9 outputs.
+/// The inputs are the board from the view of the player to
+/// move (1 = own, -1 = opponent, 0 = empty), and the player
+/// takes the empty cell with the highest output.
+#[derive(Clone)]
+pub struct TicTacToe {
+    pub board: [Cell; 9],
+    pub turn: Cell,
+}
+
+impl TicTacToe {
+    pub fn new() -> Self {
+        Self { board: [Cell::Empty; 9], turn: Cell::X }
+    }
+
+    /// The board as seen by `player`
+    pub fn input(&self, player: Cell) -> Vec<f32> {
+        self.board.iter().map(|&cell| {
+            if cell == Cell::Empty { 0.0 } else if cell == player { 1.0 } else { -1.0 }
+        }).collect()
+    }
+
+    /// Places the mark of the player to move, returns false if
+    /// the cell is taken or the game is over
+    pub fn play(&mut self, cell: usize) -> bool {
+        if self.board[cell] != Cell::Empty || self.outcome().is_some() { return false }
+        self.board[cell] = self.turn;
+        self.turn = if self.turn == Cell::X { Cell::O } else { Cell::X };
+        true
+    }
+
+    /// None while the game is still going
+    pub fn outcome(&self) -> Option<Outcome> {
+        for line in LINES {
+            let cell = self.board[line[0]];
+            if cell != Cell::Empty && line.iter().all(|&i| self.board[i] == cell) {
+                return Some(Outcome::Winner(cell));
+            }
+        }
+
+        if self.board.contains(&Cell::Empty) { None } else { Some(Outcome::Draw) }
+    }
+
+    /// Plays a full game, `x` starts. Returns the outcome.
+    pub fn play_game(x: &mut NeatNetwork, o: &mut NeatNetwork) -> Outcome {
+        let mut game = Self::new();
+        loop {
+            if let Some(outcome) = game.outcome() { return outcome }
+
+            let player = game.turn;
+            let network = if player == Cell::X { &mut *x } else { &mut *o };
+            let output = network.calculate_output(game.input(player));
+
+            // Weights are unbounded and can overflow into NaN
+            // outputs, which are the least preferred cells
+            let preference = |cell: usize| if output[cell].is_nan() { f32::NEG_INFINITY } else { output[cell] };
+            let mut preferences: Vec<usize> = (0..output.len()).collect();
+            preferences.sort_by(|&a, &b| preference(b).total_cmp(&preference(a)));
+            let cell = preferences.into_iter().find(|&cell| game.board[cell] == Cell::Empty).unwrap();
+            game.play(cell);
+        }
+    }
+
+    pub fn display(&self) {
+        for row in self.board.chunks(3) {
+            for cell in row {
+                match cell {
+                    Cell::Empty => print!(". "),
+                    Cell::X => print!("X "),
+                    Cell::O => print!("O "),
+                }
+            }
+            println!();
+        }
+        println!();
+    }
+}
+
+impl Default for TicTacToe {
+    fn default() -> Self { Self::new() }
+}
+
+/// Two games, one with each network starting. A win is
+/// worth 1.0 and a draw 0.5, so scores are in [0, 2].
+#[derive(Clone)]
+pub struct TicTacToeEvaluator;
+impl PairwiseEvaluator for TicTacToeEvaluator {
+    fn run(&mut self, a: &mut NeatNetwork, b: &mut NeatNetwork) -> (f32, f32) {
+        let score = |outcome: Outcome, player: Cell| match outcome {
+            Outcome::Winner(winner) if winner == player => 1.0,
+            Outcome::Winner(_) => 0.0,
+            Outcome::Draw => 0.5,
+        };
+
+        let first = TicTacToe::play_game(a, b);
+        let second = TicTacToe::play_game(b, a);
+        (
+            score(first, Cell::X) + score(second, Cell::O),
+            score(first, Cell::O) + score(second, Cell::X),
+        )
+    }
+}
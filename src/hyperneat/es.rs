@@ -0,0 +1,293 @@
+/* Imports */
+use std::collections::{HashMap, HashSet};
+use crate::neural_network::{builder::{BuilderNode, NetworkBuilder}, network::NeatNetwork};
+use super::{substrate::{Point, Substrate}, HyperNeatConfig, SubstrateDecoder};
+
+/// Evolvable-substrate HyperNEAT. Instead of placing hidden nodes
+/// by hand, they are placed where the CPPN pattern holds the most
+/// information: the weights leaving each input (and later each
+/// found hidden node) are sampled over a quadtree which is divided
+/// further in areas of high variance, and nodes are put in the
+/// leaves which sit on a band (a sharp change in weight).
+///
+/// Only 2D substrates are supported, hidden nodes are searched for
+/// in [-1, 1] x [-1, 1]. Hidden layers of the substrate are ignored.
+#[derive(Clone, Debug)]
+pub struct EsHyperNeatConfig {
+    /// Weight expression, max weight and activations
+    pub hyperneat: HyperNeatConfig,
+
+    /// Depth the quadtree is always divided to
+    pub initial_depth: usize,
+
+    /// Depth the quadtree is never divided beyond
+    pub max_depth: usize,
+
+    /// Quadtree cells whose weights vary more than this
+    /// are divided (between initial and max depth)
+    pub division_threshold: f32,
+
+    /// Quadtree cells whose weights vary more than this
+    /// are searched further for nodes, instead of being
+    /// treated as a single leaf
+    pub variance_threshold: f32,
+
+    /// How much a leaf needs to differ from both of its
+    /// neighbours (horizontally or vertically) to be
+    /// expressed as a node
+    pub band_threshold: f32,
+
+    /// How many times hidden nodes are searched for from
+    /// the hidden nodes which were found last
+    pub iteration_level: usize,
+}
+
+/// A cell of the quadtree, centered on (x, y) with a
+/// half side length of `width`
+struct QuadPoint {
+    x: f32,
+    y: f32,
+    width: f32,
+    level: usize,
+
+    /// CPPN output for the connection to / from this cell
+    output: Vec<f32>,
+    children: Vec<QuadPoint>,
+}
+
+impl EsHyperNeatConfig {
+    /// Searches the substrate for hidden nodes and connections and
+    /// builds the network it describes. Inputs and outputs are the
+    /// inputs and outputs of the substrate, in the same order.
+    /// Hidden nodes which aren't on any path from an input to an
+    /// output are removed.
+    pub fn decode(&self, cppn: &mut NeatNetwork, substrate: &Substrate) -> NeatNetwork {
+        assert!(substrate.dimensions() == 2, "ES-HyperNEAT only supports 2D substrates");
+        assert!(cppn.input_size() == substrate.cppn_inputs(), "CPPN inputs don't match the substrate dimensions");
+        assert!(cppn.output_size() == self.hyperneat.cppn_outputs(), "CPPN outputs don't match the config");
+
+        let mut hidden: Vec<Point> = Vec::new();
+        let mut hidden_lookup: HashMap<(u32, u32), usize> = HashMap::new();
+        let mut connections: Vec<(BuilderNode, BuilderNode, f32)> = Vec::new();
+
+        // Hidden -> hidden connections, to keep the network acyclic
+        let mut hidden_edges: Vec<(usize, usize)> = Vec::new();
+
+        /* Input to hidden */
+        let mut frontier = Vec::new();
+        for (i, point) in substrate.inputs().iter().enumerate() {
+            for (target, weight) in self.find_connections(cppn, substrate, point, true) {
+                let (index, new) = insert_hidden(&mut hidden, &mut hidden_lookup, target);
+                if new { frontier.push(index); }
+                connections.push((BuilderNode::Input(i), BuilderNode::Hidden(index), weight));
+            }
+        }
+
+        /* Hidden to hidden */
+        for _ in 0..self.iteration_level {
+            let mut found = Vec::new();
+            for from in frontier {
+                for (target, weight) in self.find_connections(cppn, substrate, &hidden[from], true) {
+                    let (to, new) = insert_hidden(&mut hidden, &mut hidden_lookup, target);
+                    if new { found.push(to); }
+
+                    let edge = (from, to);
+                    if from == to || NeatNetwork::has_cycle(hidden_edges.iter().chain(std::iter::once(&edge))) { continue; }
+                    hidden_edges.push(edge);
+                    connections.push((BuilderNode::Hidden(from), BuilderNode::Hidden(to), weight));
+                }
+            }
+            frontier = found;
+        }
+
+        /* Hidden to output, only to nodes which were found already */
+        for (i, point) in substrate.outputs().iter().enumerate() {
+            for (source, weight) in self.find_connections(cppn, substrate, point, false) {
+                if let Some(&from) = hidden_lookup.get(&key(&source)) {
+                    connections.push((BuilderNode::Hidden(from), BuilderNode::Output(i), weight));
+                }
+            }
+        }
+
+        /* Remove hidden nodes which don't lead anywhere */
+        let kept = connected_hidden(hidden.len(), &connections);
+        let mut builder = NetworkBuilder::new(self.hyperneat.activations);
+        for i in 0..substrate.inputs().len() {
+            builder.add_input(&format!("i{i}"));
+        }
+        for (i, point) in substrate.outputs().iter().enumerate() {
+            let bias = self.hyperneat.query_bias(cppn, substrate, point);
+            let node = builder.add_output(&format!("o{i}"));
+            builder.set_bias(node, bias);
+        }
+
+        let mut remapped = HashMap::new();
+        for (index, point) in hidden.iter().enumerate() {
+            if !kept.contains(&index) { continue; }
+            let bias = self.hyperneat.query_bias(cppn, substrate, point);
+            remapped.insert(index, builder.add_hidden(self.hyperneat.activations.hidden, bias));
+        }
+
+        let remap = |node: BuilderNode| match node {
+            BuilderNode::Hidden(i) => remapped.get(&i).copied(),
+            other => Some(other),
+        };
+        for (from, to, weight) in connections {
+            if let (Some(from), Some(to)) = (remap(from), remap(to)) {
+                builder.connect(from, to, weight);
+            }
+        }
+
+        builder.build().expect("ES-HyperNEAT networks are acyclic")
+    }
+
+    /// The expressed connections leaving (`outgoing`) or entering
+    /// `point`, as (other point, weight)
+    fn find_connections(&self, cppn: &mut NeatNetwork, substrate: &Substrate, point: &Point, outgoing: bool) -> Vec<(Point, f32)> {
+        let mut root = QuadPoint::new(0.0, 0.0, 1.0, 0, Vec::new());
+        self.divide(cppn, substrate, &mut root, point, outgoing);
+
+        let mut found = Vec::new();
+        self.extract(cppn, substrate, &root, point, outgoing, &mut found);
+        found
+    }
+
+    /// Samples the four children of `cell`, and keeps dividing
+    /// them until the initial depth or until they vary too little
+    fn divide(&self, cppn: &mut NeatNetwork, substrate: &Substrate, cell: &mut QuadPoint, point: &Point, outgoing: bool) {
+        let half = cell.width / 2.0;
+        for (dx, dy) in [(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)] {
+            let (x, y) = (cell.x + dx * half, cell.y + dy * half);
+            let output = query(cppn, substrate, point, &[x, y, 0.0], outgoing);
+            cell.children.push(QuadPoint::new(x, y, half, cell.level + 1, output));
+        }
+
+        let level = cell.level + 1;
+        if level < self.initial_depth || (level < self.max_depth && cell.variance() > self.division_threshold) {
+            for child in &mut cell.children {
+                self.divide(cppn, substrate, child, point, outgoing);
+            }
+        }
+    }
+
+    /// Collects the leaves of low variance areas which sit on a band
+    fn extract(&self, cppn: &mut NeatNetwork, substrate: &Substrate, cell: &QuadPoint, point: &Point, outgoing: bool, found: &mut Vec<(Point, f32)>) {
+        for child in &cell.children {
+            if child.variance() >= self.variance_threshold {
+                self.extract(cppn, substrate, child, point, outgoing, found);
+                continue;
+            }
+
+            let weight = child.output[0];
+            let mut neighbour = |dx: f32, dy: f32| -> f32 {
+                let output = query(cppn, substrate, point, &[child.x + dx, child.y + dy, 0.0], outgoing);
+                (weight - output[0]).abs()
+            };
+            let (left, right) = (neighbour(-child.width, 0.0), neighbour(child.width, 0.0));
+            let (top, bottom) = (neighbour(0.0, -child.width), neighbour(0.0, child.width));
+            let band = left.min(right).max(top.min(bottom));
+
+            if band > self.band_threshold {
+                if let Some(weight) = self.hyperneat.express(&child.output) {
+                    found.push(([child.x, child.y, 0.0], weight));
+                }
+            }
+        }
+    }
+}
+
+impl QuadPoint {
+    fn new(x: f32, y: f32, width: f32, level: usize, output: Vec<f32>) -> Self {
+        Self { x, y, width, level, output, children: Vec::new() }
+    }
+
+    /// Variance of the weights of all leaves below this cell
+    fn variance(&self) -> f32 {
+        let mut weights = Vec::new();
+        self.leaf_weights(&mut weights);
+        if weights.is_empty() { return 0.0 }
+
+        let mean = weights.iter().sum::<f32>() / weights.len() as f32;
+        weights.iter().map(|w| (w - mean).powi(2)).sum::<f32>() / weights.len() as f32
+    }
+    fn leaf_weights(&self, weights: &mut Vec<f32>) {
+        for child in &self.children {
+            if child.children.is_empty() { weights.push(child.output[0]); }
+            else { child.leaf_weights(weights); }
+        }
+    }
+}
+
+impl SubstrateDecoder for EsHyperNeatConfig {
+    fn decode(&self, cppn: &mut NeatNetwork, substrate: &Substrate) -> NeatNetwork { EsHyperNeatConfig::decode(self, cppn, substrate) }
+    fn cppn_outputs(&self) -> usize { self.hyperneat.cppn_outputs() }
+}
+
+impl Default for EsHyperNeatConfig {
+    fn default() -> Self {
+        Self {
+            hyperneat: HyperNeatConfig::default(),
+            initial_depth: 3,
+            max_depth: 5,
+            division_threshold: 0.03,
+            variance_threshold: 0.03,
+            band_threshold: 0.3,
+            iteration_level: 1,
+        }
+    }
+}
+
+/// CPPN output for `point` -> `other`, or the other way around
+fn query(cppn: &mut NeatNetwork, substrate: &Substrate, point: &Point, other: &Point, outgoing: bool) -> Vec<f32> {
+    if outgoing { cppn.calculate_output(substrate.query(point, other)) }
+    else { cppn.calculate_output(substrate.query(other, point)) }
+}
+
+fn key(point: &Point) -> (u32, u32) {
+    (point[0].to_bits(), point[1].to_bits())
+}
+
+/// Index of the hidden node at `point`, and if it was just added
+fn insert_hidden(hidden: &mut Vec<Point>, lookup: &mut HashMap<(u32, u32), usize>, point: Point) -> (usize, bool) {
+    match lookup.get(&key(&point)) {
+        Some(&index) => (index, false),
+        None => {
+            hidden.push(point);
+            lookup.insert(key(&point), hidden.len() - 1);
+            (hidden.len() - 1, true)
+        }
+    }
+}
+
+/// Hidden nodes which are reachable from an input and
+/// from which some output can be reached
+fn connected_hidden(amount: usize, connections: &[(BuilderNode, BuilderNode, f32)]) -> HashSet<usize> {
+    let mut forward = vec![Vec::new(); amount];
+    let mut backward = vec![Vec::new(); amount];
+    let (mut from_input, mut to_output) = (Vec::new(), Vec::new());
+    for &(from, to, _) in connections {
+        match (from, to) {
+            (BuilderNode::Input(_), BuilderNode::Hidden(to)) => from_input.push(to),
+            (BuilderNode::Hidden(from), BuilderNode::Output(_)) => to_output.push(from),
+            (BuilderNode::Hidden(from), BuilderNode::Hidden(to)) => {
+                forward[from].push(to);
+                backward[to].push(from);
+            },
+            _ => {},
+        }
+    }
+
+    let reachable = |start: Vec<usize>, edges: &Vec<Vec<usize>>| {
+        let mut visited: HashSet<usize> = start.iter().copied().collect();
+        let mut stack = start;
+        while let Some(node) = stack.pop() {
+            for &next in &edges[node] {
+                if visited.insert(next) { stack.push(next); }
+            }
+        }
+        visited
+    };
+
+    let reached = reachable(from_input, &forward);
+    reachable(to_output, &backward).intersection(&reached).copied().collect()
+}
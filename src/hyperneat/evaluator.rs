@@ -0,0 +1,62 @@
+/* Imports */
+use crate::{neural_network::network::NeatNetwork, trainer::fitness::{Evaluation, FitnessEvaluator}};
+use super::{substrate::Substrate, HyperNeatConfig, SubstrateDecoder};
+
+/// Lets `Evolution` evolve CPPNs. Every genome is decoded over
+/// the substrate and the decoded network is passed on to the
+/// inner fitness evaluator.
+///
+/// The evolution needs `substrate.cppn_inputs()` input nodes
+/// and `config.cppn_outputs()` output nodes. The config is
+/// either a `HyperNeatConfig` or an `EsHyperNeatConfig`.
+#[derive(Clone)]
+pub struct HyperNeatEvaluator<E: FitnessEvaluator, D: SubstrateDecoder = HyperNeatConfig> {
+    substrate: Substrate,
+    config: D,
+    evaluator: E,
+}
+
+impl<E: FitnessEvaluator, D: SubstrateDecoder> HyperNeatEvaluator<E, D> {
+    pub fn new(substrate: Substrate, config: D, evaluator: E) -> Self {
+        Self { substrate, config, evaluator }
+    }
+
+    /// Decodes a CPPN into its substrate network, e.g
+    /// for saving or exporting the best performer
+    pub fn decode(&self, cppn: &mut NeatNetwork) -> NeatNetwork {
+        self.config.decode(cppn, &self.substrate)
+    }
+
+    // Getters
+    pub fn substrate(&self) -> &Substrate { &self.substrate }
+    pub fn config(&self) -> &D { &self.config }
+    pub fn evaluator(&self) -> &E { &self.evaluator }
+}
+
+impl<E: FitnessEvaluator, D: SubstrateDecoder> FitnessEvaluator for HyperNeatEvaluator<E, D> {
+    fn run(&mut self, network: &mut NeatNetwork) -> f32 {
+        let mut decoded = self.decode(network);
+        self.evaluator.run(&mut decoded)
+    }
+
+    fn run_visualize(&mut self, network: &mut NeatNetwork) -> () {
+        let mut decoded = self.decode(network);
+        self.evaluator.run_visualize(&mut decoded)
+    }
+
+    fn evaluate(&mut self, network: &mut NeatNetwork) -> Evaluation {
+        let mut decoded = self.decode(network);
+        self.evaluator.evaluate(&mut decoded)
+    }
+
+    /// The inner evaluator sees the decoded population, each
+    /// network with the fitness of the CPPN it came from
+    fn prepare_generation(&mut self, population: &[&NeatNetwork]) {
+        let decoded: Vec<NeatNetwork> = population.iter().map(|cppn| {
+            let mut decoded = self.decode(&mut (*cppn).clone());
+            decoded.record_fitness(cppn.previous_fitness());
+            decoded
+        }).collect();
+        self.evaluator.prepare_generation(&decoded.iter().collect::<Vec<&NeatNetwork>>());
+    }
+}
@@ -0,0 +1,139 @@
+/* Imports */
+use crate::neural_network::{activation::{Activation, NetworkActivations}, builder::{BuilderNode, NetworkBuilder}, network::NeatNetwork};
+use substrate::{Point, Substrate};
+
+pub mod substrate;
+pub mod evaluator;
+pub mod es;
+
+/* Constants */
+const ORIGIN: Point = [0.0; 3];
+
+/// Anything which turns a CPPN into a network over some
+/// substrate, see `HyperNeatConfig` and `es::EsHyperNeatConfig`
+pub trait SubstrateDecoder: Clone {
+    fn decode(&self, cppn: &mut NeatNetwork, substrate: &Substrate) -> NeatNetwork;
+
+    /// How many outputs a CPPN needs to be decoded
+    fn cppn_outputs(&self) -> usize;
+}
+
+/// How the outputs of a CPPN are turned into the weights and
+/// biases of a substrate network.
+///
+/// The CPPN is queried with the coordinates of the source and
+/// target node (see `Substrate::query`) and has the outputs
+/// `[weight, bias]`, or `[weight, bias, leo]` if `leo` is set.
+/// Outputs are expected to be in [-1, 1], e.g by evolving the
+/// CPPN with `Activation::Tanh` as output activation.
+#[derive(Clone, Debug)]
+pub struct HyperNeatConfig {
+    /// Connections whose weight output is below this (in absolute
+    /// value) are not expressed. Should be in [0, 1). Unused if
+    /// `leo` is set.
+    pub weight_threshold: f32,
+
+    /// Weight outputs are scaled to [-max_weight, max_weight]
+    pub max_weight: f32,
+
+    /// Use a third CPPN output (Link Expression Output) to decide
+    /// if a connection is expressed (leo > 0.0) instead of the
+    /// weight threshold
+    pub leo: bool,
+
+    /// Activations of the decoded network
+    pub activations: NetworkActivations,
+}
+
+impl HyperNeatConfig {
+    /// How many outputs a CPPN needs to be decoded with this config
+    pub fn cppn_outputs(&self) -> usize { if self.leo { 3 } else { 2 } }
+
+    /// Queries `cppn` for every possible connection of the substrate
+    /// and builds the fixed topology network it describes. Inputs and
+    /// outputs of the network are the inputs and outputs of the
+    /// substrate, in the same order.
+    ///
+    /// The bias of each hidden / output node is the bias output of
+    /// the CPPN queried from the origin to that node.
+    pub fn decode(&self, cppn: &mut NeatNetwork, substrate: &Substrate) -> NeatNetwork {
+        assert!(cppn.input_size() == substrate.cppn_inputs(), "CPPN inputs don't match the substrate dimensions");
+        assert!(cppn.output_size() == self.cppn_outputs(), "CPPN outputs don't match the config");
+
+        let mut builder = NetworkBuilder::new(self.activations);
+        let mut layers: Vec<Vec<(Point, BuilderNode)>> = Vec::new();
+        layers.push(substrate.inputs().iter()
+            .enumerate()
+            .map(|(i, point)| (*point, builder.add_input(&format!("i{i}"))))
+            .collect());
+
+        // Empty hidden layers would disconnect the network
+        for layer in substrate.hidden().iter().filter(|layer| !layer.is_empty()) {
+            layers.push(layer.iter().map(|point| {
+                let bias = self.query_bias(cppn, substrate, point);
+                (*point, builder.add_hidden(self.activations.hidden, bias))
+            }).collect());
+        }
+
+        let mut outputs = Vec::with_capacity(substrate.outputs().len());
+        for (i, point) in substrate.outputs().iter().enumerate() {
+            let bias = self.query_bias(cppn, substrate, point);
+            let node = builder.add_output(&format!("o{i}"));
+            builder.set_bias(node, bias);
+            outputs.push((*point, node));
+        }
+        layers.push(outputs);
+
+        for pair in layers.windows(2) {
+            for (from_point, from) in &pair[0] {
+                for (to_point, to) in &pair[1] {
+                    if let Some(weight) = self.query_weight(cppn, substrate, from_point, to_point) {
+                        builder.connect(*from, *to, weight);
+                    }
+                }
+            }
+        }
+
+        builder.build().expect("Layered substrates are always valid networks")
+    }
+
+    /// The weight of `from` -> `to`, None if it isn't expressed
+    pub fn query_weight(&self, cppn: &mut NeatNetwork, substrate: &Substrate, from: &Point, to: &Point) -> Option<f32> {
+        let output = cppn.calculate_output(substrate.query(from, to));
+        self.express(&output)
+    }
+
+    /// Turns a CPPN output into a weight, None if the
+    /// connection shouldn't be expressed
+    pub fn express(&self, output: &[f32]) -> Option<f32> {
+        let weight = output[0];
+        if self.leo {
+            if output[2] > 0.0 { Some((weight * self.max_weight).clamp(-self.max_weight, self.max_weight)) } else { None }
+        }else if weight.abs() > self.weight_threshold {
+            let scaled = (weight.abs() - self.weight_threshold) / (1.0 - self.weight_threshold) * self.max_weight;
+            Some(scaled.min(self.max_weight).copysign(weight))
+        }else {
+            None
+        }
+    }
+
+    fn query_bias(&self, cppn: &mut NeatNetwork, substrate: &Substrate, point: &Point) -> f32 {
+        cppn.calculate_output(substrate.query(&ORIGIN, point))[1]
+    }
+}
+
+impl SubstrateDecoder for HyperNeatConfig {
+    fn decode(&self, cppn: &mut NeatNetwork, substrate: &Substrate) -> NeatNetwork { HyperNeatConfig::decode(self, cppn, substrate) }
+    fn cppn_outputs(&self) -> usize { HyperNeatConfig::cppn_outputs(self) }
+}
+
+impl Default for HyperNeatConfig {
+    fn default() -> Self {
+        Self {
+            weight_threshold: 0.2,
+            max_weight: 3.0,
+            leo: false,
+            activations: NetworkActivations::new(Activation::Sigmoid, Activation::Sigmoid),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+/* Imports */
+use serde_derive::{Serialize, Deserialize};
+
+/// Position of a substrate node. 2D substrates ignore
+/// the last coordinate.
+pub type Point = [f32; 3];
+
+/// The geometry which a CPPN is queried over. Nodes are
+/// laid out in layers (inputs, any amount of hidden layers
+/// and outputs), and every node of a layer may connect to
+/// every node of the next layer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Substrate {
+    /// 2 or 3, decides how many coordinates of each point
+    /// are passed to the CPPN
+    dimensions: usize,
+
+    inputs: Vec<Point>,
+    hidden: Vec<Vec<Point>>,
+    outputs: Vec<Point>,
+}
+
+impl Substrate {
+    /// A substrate with no hidden layers. Panics if `dimensions`
+    /// isn't 2 or 3.
+    pub fn new(dimensions: usize, inputs: Vec<Point>, outputs: Vec<Point>) -> Self {
+        assert!(dimensions == 2 || dimensions == 3, "Substrates need to be 2D or 3D");
+        Self { dimensions, inputs, hidden: Vec::new(), outputs }
+    }
+
+    /// Adds a hidden layer between the inputs and outputs. Hidden
+    /// layers are connected in the order they are added.
+    pub fn with_hidden_layer(&mut self, layer: Vec<Point>) -> &mut Self { self.hidden.push(layer); self }
+
+    /// `columns` x `rows` points evenly spread over [-1, 1] in x
+    /// and y, row by row. A single column / row is placed at 0.
+    pub fn grid(columns: usize, rows: usize, z: f32) -> Vec<Point> {
+        let mut points = Vec::with_capacity(columns * rows);
+        for row in 0..rows {
+            for column in 0..columns {
+                points.push([spread(column, columns), spread(row, rows), z]);
+            }
+        }
+        points
+    }
+
+    /// `amount` points evenly spread over [-1, 1] in x, at height `y`
+    pub fn line(amount: usize, y: f32) -> Vec<Point> {
+        (0..amount).map(|i| [spread(i, amount), y, 0.0]).collect()
+    }
+
+    // Getters
+    pub fn dimensions(&self) -> usize { self.dimensions }
+    pub fn inputs(&self) -> &Vec<Point> { &self.inputs }
+    pub fn hidden(&self) -> &Vec<Vec<Point>> { &self.hidden }
+    pub fn outputs(&self) -> &Vec<Point> { &self.outputs }
+
+    /// How many inputs a CPPN needs to be queried over this
+    /// substrate, the coordinates of the source and target node
+    pub fn cppn_inputs(&self) -> usize { self.dimensions * 2 }
+
+    /// The CPPN input for the connection `from` -> `to`
+    pub fn query(&self, from: &Point, to: &Point) -> Vec<f32> {
+        from[..self.dimensions].iter().chain(to[..self.dimensions].iter()).copied().collect()
+    }
+}
+
+/// Position `i` of `amount` evenly spread points over [-1, 1]
+fn spread(i: usize, amount: usize) -> f32 {
+    if amount <= 1 { 0.0 } else { -1.0 + 2.0 * i as f32 / (amount - 1) as f32 }
+}
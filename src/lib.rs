@@ -3,3 +3,5 @@ pub mod trainer;
 pub mod utils;
 pub mod debug;
 pub mod games;
+pub mod export;
+pub mod hyperneat;
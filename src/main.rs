@@ -23,6 +23,8 @@ fn main() -> () {
             create_connection: 5,
             change_weight: 850,
             toggle_weight: 1,
+            delete_connection: 0,
+            delete_node: 0,
             nothing: 20,
         })
         .weight_change_probabilities(WeightChangeProbablities {
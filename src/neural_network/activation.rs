@@ -1,12 +1,12 @@
 use serde_derive::{Serialize, Deserialize};
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub struct NetworkActivations {
     pub hidden: Activation,
     pub output: Activation
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum Activation {
     Relu,
@@ -14,6 +14,11 @@ pub enum Activation {
     LeakyRelu,
     Sigmoid,
     Softmax,
+
+    /* Mostly useful for CPPNs, see `hyperneat` */
+    Tanh,
+    Sine,
+    Gaussian,
 }
 impl Activation {
     pub fn run(&self, inputs: &Vec<f32>, index: usize) -> f32 {
@@ -22,7 +27,28 @@ impl Activation {
             Self::Relu => relu(inputs, index),
             Self::Sigmoid => sigmoid(inputs, index),
             Self::Softmax => softmax(inputs, index),
-            Self::Linear => inputs[index]
+            Self::Linear => inputs[index],
+            Self::Tanh => inputs[index].tanh(),
+            Self::Sine => inputs[index].sin(),
+            Self::Gaussian => gaussian(inputs, index),
+        }
+    }
+
+    /// Derivative of the activation of a single value `x`
+    /// (softmax of a single value is always 1)
+    pub fn derivative(&self, x: f32) -> f32 {
+        match &self {
+            Self::LeakyRelu => if x > 0.0 { 1.0 } else { LEAKY_RELU_NEGATIVE_SLOPE },
+            Self::Relu => if x > 0.0 { 1.0 } else { 0.0 },
+            Self::Sigmoid => {
+                let y = sigmoid(&vec![x], 0);
+                y * (1.0 - y)
+            },
+            Self::Softmax => 0.0,
+            Self::Linear => 1.0,
+            Self::Tanh => 1.0 - x.tanh().powi(2),
+            Self::Sine => x.cos(),
+            Self::Gaussian => -2.0 * x * gaussian(&vec![x], 0),
         }
     }
 }
@@ -38,7 +64,7 @@ pub fn relu(inputs: &Vec<f32>, index: usize) -> f32 {
     else { return 0.0 }
 }
 
-const LEAKY_RELU_NEGATIVE_SLOPE: f32 = 0.1;
+pub const LEAKY_RELU_NEGATIVE_SLOPE: f32 = 0.1;
 pub fn leaky_relu(inputs: &Vec<f32>, index: usize) -> f32 {
     if inputs[index] > 0.0 { return inputs[index] }
     else { return LEAKY_RELU_NEGATIVE_SLOPE * inputs[index] }
@@ -48,6 +74,11 @@ pub fn sigmoid(inputs: &Vec<f32>, index: usize) -> f32 {
     1.0 / (1.0 + f32::exp(-inputs[index]))
 }
 
+/// Unnormalized gaussian, e^(-x^2)
+pub fn gaussian(inputs: &Vec<f32>, index: usize) -> f32 {
+    (-inputs[index] * inputs[index]).exp()
+}
+
 fn softmax(inputs: &Vec<f32>, index: usize) -> f32 {
     let mut exponent_sum = 0.0;
     for i in 0..inputs.len() {
@@ -0,0 +1,210 @@
+/* Imports */
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+use crate::trainer::config::network_config::NetworkConfig;
+use super::{activation::{Activation, NetworkActivations}, connection_gene::ConnectionGene, network::NeatNetwork, node_gene::{NodeGene, NodeGeneType}, validation::Violation};
+
+/* Constants */
+/// Same as the bias `NodeGene::new` starts out with
+const DEFAULT_OUTPUT_BIAS: f32 = 0.1;
+
+/// A node of a network that is being built. Indexes are only
+/// resolved in `NetworkBuilder::build`, because outputs, the bias
+/// and hidden nodes are placed after all of the inputs.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum BuilderNode {
+    Input(usize),
+    Output(usize),
+
+    /// The bias node, which always outputs 1.0
+    Bias,
+    Hidden(usize),
+}
+
+/// Builds a network node by node instead of from raw connection
+/// genes. Innovation numbers are taken from (or added to) the
+/// innovation registry, so networks built here line up with the
+/// rest of an `Evolution` during crossover.
+/// 
+/// ```ignore
+/// let mut builder = NetworkBuilder::new(NetworkActivations::default());
+/// let x = builder.add_input("x");
+/// let out = builder.add_output("out");
+/// let hidden = builder.add_hidden(Activation::Relu, 0.0);
+/// builder.connect(x, hidden, 1.5).connect(hidden, out, -0.5);
+/// let network = builder.build().unwrap();
+/// ```
+pub struct NetworkBuilder {
+    input_names: Vec<String>,
+    output_names: Vec<String>,
+    output_biases: Vec<f32>,
+
+    /// (activation, bias) of every hidden node
+    hidden: Vec<(Activation, f32)>,
+
+    /// (from, to, weight, enabled)
+    connections: Vec<(BuilderNode, BuilderNode, f32, bool)>,
+
+    activations: NetworkActivations,
+    network_config: Arc<NetworkConfig>,
+    global_innovation: Arc<Mutex<usize>>,
+    global_occupied_connections: Arc<Mutex<HashMap<(usize, usize), usize>>>,
+}
+
+impl NetworkBuilder {
+    /// A builder with its own, empty innovation registry
+    pub fn new(activations: NetworkActivations) -> Self {
+        Self {
+            input_names: Vec::new(),
+            output_names: Vec::new(),
+            output_biases: Vec::new(),
+            hidden: Vec::new(),
+            connections: Vec::new(),
+            activations,
+            network_config: Arc::default(),
+            global_innovation: Arc::default(),
+            global_occupied_connections: Arc::default(),
+        }
+    }
+
+    /// Assign innovation numbers against an existing registry, e.g the
+    /// global innovation number and occupied connections of an evolution
+    pub fn with_registry(
+        &mut self,
+        global_innovation: Arc<Mutex<usize>>,
+        global_occupied_connections: Arc<Mutex<HashMap<(usize, usize), usize>>>
+    ) -> &mut Self {
+        self.global_innovation = global_innovation;
+        self.global_occupied_connections = global_occupied_connections;
+        self
+    }
+    pub fn with_network_config(&mut self, config: Arc<NetworkConfig>) -> &mut Self { self.network_config = config; self }
+
+    pub fn add_input(&mut self, name: &str) -> BuilderNode {
+        self.input_names.push(name.to_string());
+        BuilderNode::Input(self.input_names.len() - 1)
+    }
+    pub fn add_output(&mut self, name: &str) -> BuilderNode {
+        self.output_names.push(name.to_string());
+        self.output_biases.push(DEFAULT_OUTPUT_BIAS);
+        BuilderNode::Output(self.output_names.len() - 1)
+    }
+
+    /// Adds a hidden node with its own activation function
+    pub fn add_hidden(&mut self, activation: Activation, bias: f32) -> BuilderNode {
+        self.hidden.push((activation, bias));
+        BuilderNode::Hidden(self.hidden.len() - 1)
+    }
+
+    /// Overrides the bias of an output or hidden node. Panics
+    /// for inputs and the bias node, which have no bias to set
+    pub fn set_bias(&mut self, node: BuilderNode, bias: f32) -> &mut Self {
+        match node {
+            BuilderNode::Output(i) => self.output_biases[i] = bias,
+            BuilderNode::Hidden(i) => self.hidden[i].1 = bias,
+            _ => panic!("Can't set the bias of {node:?}"),
+        }
+        self
+    }
+
+    /// Finds an input or output by its name
+    pub fn node(&self, name: &str) -> Option<BuilderNode> {
+        self.input_names.iter().position(|e| e == name).map(BuilderNode::Input)
+            .or_else(|| self.output_names.iter().position(|e| e == name).map(BuilderNode::Output))
+    }
+
+    pub fn connect(&mut self, from: BuilderNode, to: BuilderNode, weight: f32) -> &mut Self {
+        self.connections.push((from, to, weight, true));
+        self
+    }
+    /// Adds a connection gene which starts out disabled
+    pub fn connect_disabled(&mut self, from: BuilderNode, to: BuilderNode, weight: f32) -> &mut Self {
+        self.connections.push((from, to, weight, false));
+        self
+    }
+
+    /// Connects two inputs / outputs by name. Panics if
+    /// any of the names don't exist.
+    pub fn connect_named(&mut self, from: &str, to: &str, weight: f32) -> &mut Self {
+        let from = self.node(from).unwrap_or_else(|| panic!("No input or output named {from:?}"));
+        let to = self.node(to).unwrap_or_else(|| panic!("No input or output named {to:?}"));
+        self.connect(from, to, weight)
+    }
+
+    pub fn input_names(&self) -> &Vec<String> { &self.input_names }
+    pub fn output_names(&self) -> &Vec<String> { &self.output_names }
+
+    /// Index of a node in the built network
+    pub fn index_of(&self, node: BuilderNode) -> usize {
+        let (inputs, outputs) = (self.input_names.len(), self.output_names.len());
+        match node {
+            BuilderNode::Input(i) => i,
+            BuilderNode::Output(i) => inputs + i,
+            BuilderNode::Bias => inputs + outputs,
+            BuilderNode::Hidden(i) => inputs + outputs + 1 + i,
+        }
+    }
+
+    /// Assembles the network, registers new innovations and
+    /// validates the result, see `NeatNetwork::validate`.
+    pub fn build(&self) -> Result<NeatNetwork, Vec<Violation>> {
+        let (inputs, outputs) = (self.input_names.len(), self.output_names.len());
+
+        let mut node_genes = Vec::with_capacity(inputs + outputs + 1 + self.hidden.len());
+        for _ in 0..inputs {
+            node_genes.push(NodeGene::new(NodeGeneType::Input, 0.0));
+        }
+        for &bias in &self.output_biases {
+            let mut node_gene = NodeGene::new(NodeGeneType::Output, 1.0);
+            node_gene.set_bias(bias);
+            node_genes.push(node_gene);
+        }
+
+        /* Bias node */
+        let mut bias = NodeGene::new(NodeGeneType::Input, 0.0);
+        bias.set_bias(1.);
+        node_genes.push(bias);
+
+        for &(activation, bias) in &self.hidden {
+            let mut node_gene = NodeGene::new(NodeGeneType::Regular, 0.5);
+            node_gene.set_bias(bias);
+            node_gene.set_activation_function(Some(activation));
+            node_genes.push(node_gene);
+        }
+
+        let mut connection_genes = Vec::with_capacity(self.connections.len());
+        {
+            let mut global_occupied = self.global_occupied_connections.lock().unwrap();
+            let mut global_innovation = self.global_innovation.lock().unwrap();
+
+            for &(from, to, weight, enabled) in &self.connections {
+                let key = (self.index_of(from), self.index_of(to));
+                let innovation = match global_occupied.get(&key) {
+                    Some(innovation) => *innovation,
+                    None => {
+                        // The registry stores the latest innovation
+                        // number that was handed out
+                        let innovation = if global_occupied.is_empty() { 0 } else { *global_innovation + 1 };
+                        *global_innovation = innovation;
+                        global_occupied.insert(key, innovation);
+                        innovation
+                    }
+                };
+
+                let mut connection = ConnectionGene::new(key.0, key.1, weight, innovation);
+                connection.set_enabled(enabled);
+                connection_genes.push(connection);
+            }
+        }
+
+        let network = NeatNetwork::from_genes(
+            inputs, outputs,
+            node_genes, connection_genes,
+            self.global_innovation.clone(),
+            self.global_occupied_connections.clone(),
+            self.activations,
+            self.network_config.clone()
+        );
+
+        network.validate().map(|_| network)
+    }
+}
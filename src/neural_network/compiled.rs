@@ -0,0 +1,139 @@
+/* Imports */
+use super::{activation::{Activation, NetworkActivations}, network::NeatNetwork, node_gene::NodeGeneType};
+
+/// What a compiled node does with its incoming values
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CompiledNodeKind {
+    /// Reads input number `n` from the input vector
+    Input(usize),
+
+    /// The bias node, always yields its bias
+    Constant(f32),
+
+    /// bias + incoming, then the activation of this node
+    /// (the hidden activation unless the node overrides it)
+    Hidden(Activation),
+
+    /// bias + incoming without any activation. The output
+    /// activation is applied to all outputs at once.
+    Output,
+}
+
+#[derive(Clone, Debug)]
+pub struct CompiledNode {
+    /// Index of the node gene this was compiled from
+    pub index: usize,
+    pub kind: CompiledNodeKind,
+    pub bias: f32,
+
+    /// (node index, weight) of every enabled incoming connection,
+    /// in the same order `calculate_output` sums them. A node which
+    /// comes later in the topological order reads as zero.
+    pub incoming: Vec<(usize, f32)>,
+}
+
+/// A flat, read-only snapshot of a `NeatNetwork` in evaluation
+/// order. Disabled genes are dropped and every node lists its own
+/// incoming weights, which is what exporters need to emit a graph
+/// that behaves exactly like `calculate_output`. Plastic
+/// connections are frozen at their evolved weight.
+#[derive(Clone, Debug)]
+pub struct CompiledNetwork {
+    input_size: usize,
+    output_size: usize,
+
+    /// Nodes in topological order
+    nodes: Vec<CompiledNode>,
+
+    /// Node indexes of the outputs, in output order
+    outputs: Vec<usize>,
+
+    /// Amount of node genes in the source network
+    node_count: usize,
+    activations: NetworkActivations,
+}
+
+impl CompiledNetwork {
+    /// Compiles the network. Returns None if the enabled
+    /// connections contain a cycle.
+    pub fn new(network: &NeatNetwork) -> Option<Self> {
+        let order = network.topological_sort()?;
+        let node_genes = network.node_genes();
+        let connection_genes = network.get_genes();
+        let input_size = network.input_size();
+        let output_size = network.output_size();
+
+        let nodes = order.into_iter().map(|index| {
+            let node = &node_genes[index];
+            let kind = match node.node_type() {
+                NodeGeneType::Input if index < input_size => CompiledNodeKind::Input(index),
+                NodeGeneType::Input => CompiledNodeKind::Constant(node.bias()),
+                NodeGeneType::Regular => CompiledNodeKind::Hidden(
+                    node.activation_function().unwrap_or(network.activations().hidden)
+                ),
+                NodeGeneType::Output => CompiledNodeKind::Output,
+            };
+            let incoming = node.incoming_connection_indexes()
+                .iter()
+                .map(|&i| &connection_genes[i])
+                .filter(|conn| conn.enabled())
+                .map(|conn| (conn.node_in(), conn.weight()))
+                .collect();
+
+            CompiledNode { index, kind, bias: node.bias(), incoming }
+        }).collect();
+
+        Some(Self {
+            input_size,
+            output_size,
+            nodes,
+            outputs: (input_size..input_size + output_size).collect(),
+            node_count: node_genes.len(),
+            activations: network.activations(),
+        })
+    }
+
+    /// Evaluates the compiled graph, same result as
+    /// `NeatNetwork::calculate_output`
+    pub fn evaluate(&self, input: &[f32]) -> Vec<f32> {
+        assert!(input.len() == self.input_size);
+        let mut values = vec![0.0; self.node_count];
+
+        // Inputs and constants are set before the pass starts,
+        // while everything else starts at zero
+        for node in &self.nodes {
+            match node.kind {
+                CompiledNodeKind::Input(n) => values[node.index] = input[n],
+                CompiledNodeKind::Constant(value) => values[node.index] = value,
+                _ => {},
+            }
+        }
+
+        for node in &self.nodes {
+            values[node.index] = match node.kind {
+                CompiledNodeKind::Input(_) | CompiledNodeKind::Constant(_) => continue,
+                CompiledNodeKind::Hidden(activation) => activation.run(&vec![self.weighted_sum(node, &values)], 0),
+                CompiledNodeKind::Output => self.weighted_sum(node, &values),
+            };
+        }
+
+        let outputs: Vec<f32> = self.outputs.iter().map(|&i| values[i]).collect();
+        (0..outputs.len()).map(|i| self.activations.output.run(&outputs, i)).collect()
+    }
+
+    fn weighted_sum(&self, node: &CompiledNode, values: &[f32]) -> f32 {
+        let mut sum = node.bias;
+        for &(from, weight) in &node.incoming {
+            sum += values[from] * weight;
+        }
+        sum
+    }
+
+    // Getters
+    pub fn input_size(&self) -> usize { self.input_size }
+    pub fn output_size(&self) -> usize { self.output_size }
+    pub fn nodes(&self) -> &Vec<CompiledNode> { &self.nodes }
+    pub fn outputs(&self) -> &Vec<usize> { &self.outputs }
+    pub fn node_count(&self) -> usize { self.node_count }
+    pub fn activations(&self) -> NetworkActivations { self.activations }
+}
@@ -1,6 +1,8 @@
 use rand::{rngs::ThreadRng, thread_rng, Rng};
+use rand_distr::{Distribution, StandardNormal};
 use serde_derive::{Serialize, Deserialize};
-use crate::trainer::config::mutation::WeightChangeProbablities;
+use crate::trainer::config::mutation::{GaussianWeights, WeightBounds, WeightChangeProbablities};
+use super::plasticity::{HebbianRule, PLASTIC_DELTA_LIMIT};
 
 /// A connection between two `NodeGenes`
 #[derive(Clone, Serialize, Deserialize)]
@@ -19,11 +21,20 @@ pub struct ConnectionGene {
 
     /// The local innovation number
     innovation_number: usize,
+
+    /// Learning rule which changes the weight during an
+    /// episode. None = static weight
+    plasticity: Option<HebbianRule>,
+
+    /// How far the weight has been changed by `plasticity`
+    /// since the last `NeatNetwork::reset_plasticity`
+    #[serde(skip)]
+    plastic_delta: f32,
 }
 
 impl ConnectionGene {
     pub fn new(node_in: usize, node_out: usize, weight: f32, innovation_number: usize) -> Self {
-        Self { node_in, node_out, weight, enabled: true, innovation_number }
+        Self { node_in, node_out, weight, enabled: true, innovation_number, plasticity: None, plastic_delta: 0.0 }
     }
     
     // Getters
@@ -32,24 +43,78 @@ impl ConnectionGene {
     pub fn weight(&self) -> f32 { self.weight }
     pub fn enabled(&self) -> bool { self.enabled }
     pub fn innovation_number(&self) -> usize { self.innovation_number }
+    pub fn plasticity(&self) -> Option<HebbianRule> { self.plasticity }
+    pub fn plastic_delta(&self) -> f32 { self.plastic_delta }
+
+    /// The evolved weight plus what has been learned this episode
+    pub fn effective_weight(&self) -> f32 { self.weight + self.plastic_delta }
 
     // Setters
     pub fn set_enabled(&mut self, enabled: bool) {
         self.enabled = enabled
     }
+    pub fn set_weight(&mut self, weight: f32) {
+        self.weight = weight
+    }
+    pub fn set_plasticity(&mut self, plasticity: Option<HebbianRule>) {
+        self.plasticity = plasticity
+    }
+
+    /// Applies the learning rule (if any) for some pre-
+    /// and post-synaptic activation
+    pub fn learn(&mut self, pre: f32, post: f32) {
+        if let Some(rule) = self.plasticity {
+            self.plastic_delta = (self.plastic_delta + rule.delta(pre, post)).clamp(-PLASTIC_DELTA_LIMIT, PLASTIC_DELTA_LIMIT);
+        }
+    }
+    pub fn reset_plasticity(&mut self) {
+        self.plastic_delta = 0.0;
+    }
+
+    /// Makes this connection plastic, or changes its
+    /// learning rule if it already is
+    pub fn mutate_plasticity(&mut self, coefficient_step: f32) {
+        match &mut self.plasticity {
+            Some(rule) => rule.mutate(coefficient_step),
+            None => self.plasticity = Some(HebbianRule::default()),
+        }
+    }
 
     pub fn mutate_weight(&mut self, weight_change_prob: &WeightChangeProbablities) -> () {
+        self.mutate_weight_scaled(weight_change_prob, 1.0);
+    }
+
+    /// Perturbs or replaces the weight, see `GaussianWeights`.
+    /// `step_size` scales `sigma`
+    pub fn mutate_weight_gaussian(&mut self, config: &GaussianWeights, step_size: f32) {
+        let mut rng = thread_rng();
+        let roll: f32 = rng.gen();
+        let noise: f32 = StandardNormal.sample(&mut rng);
+        if roll < config.perturb_probability {
+            self.weight += noise * config.sigma * step_size;
+        }else if roll < config.perturb_probability + config.replace_probability {
+            self.weight = noise * config.replace_sigma;
+        }
+    }
+
+    pub fn clamp_weight(&mut self, bounds: &WeightBounds) {
+        self.weight = self.weight.clamp(bounds.min, bounds.max);
+    }
+
+    /// Like `mutate_weight` but additions and multiplications
+    /// move the weight `step_size` times as far
+    pub fn mutate_weight_scaled(&mut self, weight_change_prob: &WeightChangeProbablities, step_size: f32) {
         let mut rng = thread_rng();
         let WeightChangeProbablities {
             addition_small, addition_large, multiplication_small,
             multiplication_large, change_sign } = weight_change_prob;
 
-        let probabilities: Vec<(&usize, fn(&mut f32, ThreadRng))> = vec![
-            (addition_small, |i: &mut f32, mut rng: ThreadRng|       { *i += rng.gen_range(-0.2..0.2) }),
-            (addition_large, |i: &mut f32, mut rng: ThreadRng|       { *i += rng.gen_range(-1.5..1.5) }),
-            (multiplication_small, |i: &mut f32, mut rng: ThreadRng| { *i *= rng.gen_range(0.8..1.2) }),
-            (multiplication_large, |i: &mut f32, mut rng: ThreadRng| { *i *= rng.gen_range(0.3..1.7) }),
-            (change_sign, |i: &mut f32, _: ThreadRng|          { *i *= -1. }),
+        let probabilities: Vec<(&usize, fn(&mut f32, ThreadRng, f32))> = vec![
+            (addition_small, |i: &mut f32, mut rng: ThreadRng, step: f32|       { *i += rng.gen_range(-0.2..0.2) * step }),
+            (addition_large, |i: &mut f32, mut rng: ThreadRng, step: f32|       { *i += rng.gen_range(-1.5..1.5) * step }),
+            (multiplication_small, |i: &mut f32, mut rng: ThreadRng, step: f32| { *i *= 1.0 + rng.gen_range(-0.2..0.2) * step }),
+            (multiplication_large, |i: &mut f32, mut rng: ThreadRng, step: f32| { *i *= 1.0 + rng.gen_range(-0.7..0.7) * step }),
+            (change_sign, |i: &mut f32, _: ThreadRng, _: f32|                   { *i *= -1. }),
         ];
 
         let total: usize = probabilities.iter().map(|e| e.0).sum();
@@ -58,7 +123,7 @@ impl ConnectionGene {
         for &(probability, func) in probabilities.iter() {
             cumulative += probability;
             if random_number < cumulative {
-                (func)(&mut self.weight, rng);
+                (func)(&mut self.weight, rng, step_size);
                 //debug
                 break;
             }
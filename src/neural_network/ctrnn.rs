@@ -0,0 +1,107 @@
+/* Imports */
+use super::{network::NeatNetwork, node_gene::NodeGeneType};
+
+/// How `NeatNetwork::step` integrates the node states
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Integration {
+    /// One derivative per step, cheap but needs a small dt
+    #[default]
+    Euler,
+
+    /// Classic 4th order Runge-Kutta
+    RungeKutta4,
+}
+
+impl NeatNetwork {
+    /// Advances the network `dt` time units as a continuous time
+    /// recurrent neural network (CTRNN). Every non input node has
+    /// a state y which follows
+    ///
+    /// `time_constant * dy/dt = -y + sum(weight * incoming output)`
+    ///
+    /// and outputs `activation(y + bias)`. Unlike `calculate_output`
+    /// the state is kept between calls, use `reset_state` between
+    /// episodes. Connections may form cycles here, which mutations
+    /// only create if `CtrnnConfig::recurrent` is set.
+    ///
+    /// Integrates with the method set in `NetworkConfig::ctrnn`,
+    /// or `Integration::Euler` if there is none.
+    pub fn step(&mut self, input: Vec<f32>, dt: f32) -> Vec<f32> {
+        let integration = self.network_config().ctrnn.map(|c| c.integration).unwrap_or_default();
+        self.step_with(input, dt, integration)
+    }
+
+    /// Same as `step` but with some specific integration method
+    pub fn step_with(&mut self, input: Vec<f32>, dt: f32, integration: Integration) -> Vec<f32> {
+        assert!(input.len() == self.input_size());
+        let state: Vec<f32> = self.node_genes().iter().map(|n| n.state()).collect();
+
+        let next = match integration {
+            Integration::Euler => {
+                let k1 = self.derivative(&input, &state);
+                add_scaled(&state, &k1, dt)
+            },
+            Integration::RungeKutta4 => {
+                let k1 = self.derivative(&input, &state);
+                let k2 = self.derivative(&input, &add_scaled(&state, &k1, dt / 2.0));
+                let k3 = self.derivative(&input, &add_scaled(&state, &k2, dt / 2.0));
+                let k4 = self.derivative(&input, &add_scaled(&state, &k3, dt));
+                (0..state.len())
+                    .map(|i| state[i] + dt / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]))
+                    .collect()
+            },
+        };
+
+        for (node_gene, y) in self.node_genes_mut().iter_mut().zip(next) {
+            node_gene.set_state(y);
+        }
+
+        let outputs = self.node_outputs(&input, &self.node_genes().iter().map(|n| n.state()).collect::<Vec<f32>>());
+        let outputs = outputs[self.input_size()..self.input_size() + self.output_size()].to_vec();
+        let activation = self.activations().output;
+        (0..outputs.len()).map(|i| activation.run(&outputs, i)).collect()
+    }
+
+    /// Sets the state of every node back to 0
+    pub fn reset_state(&mut self) {
+        for node_gene in self.node_genes_mut() {
+            node_gene.set_state(0.0);
+        }
+    }
+
+    /// What every node outputs for some state. Output nodes
+    /// aren't activated, like in `calculate_output`
+    fn node_outputs(&self, input: &[f32], state: &[f32]) -> Vec<f32> {
+        let hidden = self.activations().hidden;
+        self.node_genes().iter().enumerate().map(|(index, node)| {
+            if index < self.input_size() { return input[index] }
+            if self.is_bias(index) { return node.bias() }
+
+            let sum = state[index] + node.bias();
+            match node.node_type() {
+                NodeGeneType::Regular => node.activation_function().unwrap_or(hidden).run(&vec![sum], 0),
+                _ => sum,
+            }
+        }).collect()
+    }
+
+    /// dy/dt of every node. Inputs and the bias don't change
+    fn derivative(&self, input: &[f32], state: &[f32]) -> Vec<f32> {
+        let outputs = self.node_outputs(input, state);
+        let mut incoming = vec![0.0; state.len()];
+        for connection in self.get_genes() {
+            if !connection.enabled() { continue; }
+            incoming[connection.node_out()] += outputs[connection.node_in()] * connection.effective_weight();
+        }
+
+        self.node_genes().iter().enumerate().map(|(index, node)| {
+            if node.node_type() == NodeGeneType::Input { 0.0 }
+            else { (incoming[index] - state[index]) / node.time_constant() }
+        }).collect()
+    }
+}
+
+/// a + b * scale
+fn add_scaled(a: &[f32], b: &[f32], scale: f32) -> Vec<f32> {
+    a.iter().zip(b).map(|(a, b)| a + b * scale).collect()
+}
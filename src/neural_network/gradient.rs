@@ -0,0 +1,237 @@
+/* Imports */
+use super::{activation::Activation, network::NeatNetwork, node_gene::NodeGeneType};
+
+/* Constants */
+/// Outputs are clamped to this before taking the logarithm
+const CROSS_ENTROPY_EPSILON: f32 = 1e-7;
+
+/// What `fine_tune` minimizes for every (input, target) pair
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Loss {
+    /// mean((output - target)^2)
+    MeanSquaredError,
+
+    /// -sum(target * ln(output)), for networks with a
+    /// softmax or sigmoid output activation
+    CrossEntropy,
+}
+
+/// How the weights are moved along the gradient
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Optimizer {
+    Sgd { learning_rate: f32 },
+    Adam { learning_rate: f32, beta1: f32, beta2: f32, epsilon: f32 },
+}
+
+/// The gradient of the loss for a single sample. Indexed like
+/// the connection genes and node genes of the network.
+#[derive(Clone, Debug)]
+pub struct Gradient {
+    pub loss: f32,
+
+    /// Disabled connections always have a zero gradient
+    pub weights: Vec<f32>,
+
+    /// Inputs and the bias node always have a zero gradient
+    pub biases: Vec<f32>,
+}
+
+/// First and second moment estimates for Adam
+struct OptimizerState {
+    optimizer: Optimizer,
+    steps: i32,
+    first_moment: Vec<f32>,
+    second_moment: Vec<f32>,
+}
+
+impl Loss {
+    pub fn loss(&self, output: &[f32], target: &[f32]) -> f32 {
+        match self {
+            Self::MeanSquaredError => {
+                output.iter().zip(target).map(|(o, t)| (o - t).powi(2)).sum::<f32>() / output.len() as f32
+            },
+            Self::CrossEntropy => {
+                -output.iter().zip(target).map(|(o, t)| t * o.max(CROSS_ENTROPY_EPSILON).ln()).sum::<f32>()
+            },
+        }
+    }
+
+    /// d loss / d output
+    pub fn derivative(&self, output: &[f32], target: &[f32]) -> Vec<f32> {
+        match self {
+            Self::MeanSquaredError => {
+                output.iter().zip(target).map(|(o, t)| 2.0 * (o - t) / output.len() as f32).collect()
+            },
+            Self::CrossEntropy => {
+                output.iter().zip(target).map(|(o, t)| {
+                    if *o > CROSS_ENTROPY_EPSILON { -t / o } else { 0.0 }
+                }).collect()
+            },
+        }
+    }
+}
+
+impl Optimizer {
+    pub fn sgd(learning_rate: f32) -> Self {
+        Self::Sgd { learning_rate }
+    }
+
+    /// Adam with the usual defaults (0.9, 0.999, 1e-8)
+    pub fn adam(learning_rate: f32) -> Self {
+        Self::Adam { learning_rate, beta1: 0.9, beta2: 0.999, epsilon: 1e-8 }
+    }
+}
+
+impl OptimizerState {
+    fn new(optimizer: Optimizer, parameters: usize) -> Self {
+        Self {
+            optimizer,
+            steps: 0,
+            first_moment: vec![0.0; parameters],
+            second_moment: vec![0.0; parameters],
+        }
+    }
+
+    /// Moves `parameters` against `gradient`
+    fn step(&mut self, parameters: &mut [f32], gradient: &[f32]) {
+        self.steps += 1;
+        match self.optimizer {
+            Optimizer::Sgd { learning_rate } => {
+                for (parameter, g) in parameters.iter_mut().zip(gradient) {
+                    *parameter -= learning_rate * g;
+                }
+            },
+            Optimizer::Adam { learning_rate, beta1, beta2, epsilon } => {
+                let (correction1, correction2) = (1.0 - beta1.powi(self.steps), 1.0 - beta2.powi(self.steps));
+                for (i, (parameter, g)) in parameters.iter_mut().zip(gradient).enumerate() {
+                    self.first_moment[i] = beta1 * self.first_moment[i] + (1.0 - beta1) * g;
+                    self.second_moment[i] = beta2 * self.second_moment[i] + (1.0 - beta2) * g * g;
+                    let (m, v) = (self.first_moment[i] / correction1, self.second_moment[i] / correction2);
+                    *parameter -= learning_rate * m / (v.sqrt() + epsilon);
+                }
+            },
+        }
+    }
+}
+
+impl NeatNetwork {
+    /// Reverse-mode differentiation of `loss` for a single sample,
+    /// through the same computation `calculate_output` does (plastic
+    /// connections count with their evolved weight).
+    ///
+    /// Returns None if the network contains a cycle.
+    pub fn gradient(&self, input: &[f32], target: &[f32], loss: Loss) -> Option<Gradient> {
+        assert!(input.len() == self.input_size() && target.len() == self.output_size());
+        let order = self.topological_sort()?;
+        let nodes = self.node_genes();
+        let connections = self.get_genes();
+        let hidden = self.activations().hidden;
+
+        /* Forward, keeping the sums before activation */
+        let mut sums = vec![0.0; nodes.len()];
+        let mut values = vec![0.0; nodes.len()];
+        values[..input.len()].copy_from_slice(input);
+        let bias_index = self.input_size() + self.output_size();
+        values[bias_index] = nodes[bias_index].bias();
+
+        for &index in &order {
+            let node = &nodes[index];
+            if node.node_type() == NodeGeneType::Input { continue; }
+
+            let mut sum = node.bias();
+            for &incoming in node.incoming_connection_indexes() {
+                let connection = &connections[incoming];
+                if !connection.enabled() { continue; }
+                sum += values[connection.node_in()] * connection.weight();
+            }
+            sums[index] = sum;
+            values[index] = match node.node_type() {
+                NodeGeneType::Regular => node.activation_function().unwrap_or(hidden).run(&vec![sum], 0),
+                _ => sum,
+            };
+        }
+
+        let outputs_range = self.input_size()..bias_index;
+        let raw_outputs = values[outputs_range.clone()].to_vec();
+        let output_activation = self.activations().output;
+        let outputs: Vec<f32> = (0..raw_outputs.len()).map(|i| output_activation.run(&raw_outputs, i)).collect();
+
+        /* Backward */
+        let output_gradient = loss.derivative(&outputs, target);
+        let mut deltas = vec![0.0; nodes.len()];
+        let raw_gradient = match output_activation {
+            // Full jacobian, every output depends on every sum
+            Activation::Softmax => {
+                let dot: f32 = output_gradient.iter().zip(&outputs).map(|(g, o)| g * o).sum();
+                outputs.iter().zip(&output_gradient).map(|(o, g)| o * (g - dot)).collect::<Vec<f32>>()
+            },
+            activation => raw_outputs.iter().zip(&output_gradient).map(|(x, g)| activation.derivative(*x) * g).collect(),
+        };
+        deltas[outputs_range].copy_from_slice(&raw_gradient);
+
+        let mut weights = vec![0.0; connections.len()];
+        let mut biases = vec![0.0; nodes.len()];
+        for &index in order.iter().rev() {
+            let node = &nodes[index];
+            if node.node_type() == NodeGeneType::Input { continue; }
+
+            // Everything this node feeds into has its delta by now
+            if node.node_type() == NodeGeneType::Regular {
+                let activation = node.activation_function().unwrap_or(hidden);
+                deltas[index] *= activation.derivative(sums[index]);
+            }
+            biases[index] = deltas[index];
+
+            for &incoming in node.incoming_connection_indexes() {
+                let connection = &connections[incoming];
+                if !connection.enabled() { continue; }
+                weights[incoming] = deltas[index] * values[connection.node_in()];
+                deltas[connection.node_in()] += deltas[index] * connection.weight();
+            }
+        }
+
+        Some(Gradient { loss: loss.loss(&outputs, target), weights, biases })
+    }
+
+    /// Trains the weights and biases of this network (the topology
+    /// stays the same) on `dataset` of (input, target) pairs, taking
+    /// one optimizer step per sample. The new weights are written
+    /// straight into the genome.
+    ///
+    /// Returns the average loss of the last epoch, or None if the
+    /// network contains a cycle.
+    pub fn fine_tune(&mut self, dataset: &[(Vec<f32>, Vec<f32>)], loss: Loss, optimizer: Optimizer, epochs: usize) -> Option<f32> {
+        let connections = self.get_genes().len();
+        let mut parameters: Vec<f32> = self.get_genes().iter().map(|c| c.weight())
+            .chain(self.node_genes().iter().map(|n| n.bias()))
+            .collect();
+        let mut state = OptimizerState::new(optimizer, parameters.len());
+        let mut average_loss = 0.0;
+
+        for _ in 0..epochs {
+            let mut total_loss = 0.0;
+            for (input, target) in dataset {
+                let gradient = self.gradient(input, target, loss)?;
+                total_loss += gradient.loss;
+
+                let mut flat = gradient.weights;
+                flat.extend(gradient.biases);
+                state.step(&mut parameters, &flat);
+                self.set_parameters(&parameters[..connections], &parameters[connections..]);
+            }
+            average_loss = total_loss / dataset.len().max(1) as f32;
+        }
+
+        Some(average_loss)
+    }
+
+    /// Overwrites every connection weight and node bias
+    fn set_parameters(&mut self, weights: &[f32], biases: &[f32]) {
+        for (connection, &weight) in self.connection_genes_mut().iter_mut().zip(weights) {
+            connection.set_weight(weight);
+        }
+        for (node_gene, &bias) in self.node_genes_mut().iter_mut().zip(biases) {
+            node_gene.set_bias(bias);
+        }
+    }
+}
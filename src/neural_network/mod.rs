@@ -4,3 +4,11 @@ pub mod node_gene;
 pub mod connection_gene;
 pub mod activation;
 pub mod average;
+pub mod compiled;
+pub mod validation;
+pub mod builder;
+pub mod plasticity;
+pub mod ctrnn;
+pub mod gradient;
+pub mod mutation_operator;
+pub mod mutation_record;
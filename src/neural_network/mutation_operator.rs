@@ -0,0 +1,87 @@
+/* Imports */
+use std::sync::Arc;
+use rand::rngs::ThreadRng;
+use super::network::{InnovationRegistry, NeatNetwork};
+
+/// One way of mutating a genome. The built in mutations below
+/// are operators too, and custom ones are registered with
+/// `EvolutionBuilder::mutation_operator`.
+///
+/// New connections should be added with `NeatNetwork::add_connection`,
+/// which gives them the innovation number every other network
+/// uses for the same connection. `registry` is the innovation
+/// counter and (node_in, node_out) -> innovation number map
+/// shared by the whole population.
+///
+/// ```ignore
+/// /// Connects a random sensor to a random output
+/// struct ConnectSensor;
+/// impl MutationOperator for ConnectSensor {
+///     fn mutate(&self, network: &mut NeatNetwork, rng: &mut ThreadRng, _: &InnovationRegistry) {
+///         let sensor = rng.gen_range(0..network.input_size());
+///         let output = network.input_size() + rng.gen_range(0..network.output_size());
+///         network.add_connection(sensor, output, rng.gen_range(-1.0..1.0));
+///     }
+/// }
+/// Evolution::new().mutation_operator(ConnectSensor, 5.0);
+/// ```
+pub trait MutationOperator: Send + Sync {
+    fn mutate(&self, network: &mut NeatNetwork, rng: &mut ThreadRng, registry: &InnovationRegistry);
+}
+
+/// An operator and how often it fires, see
+/// `NetworkConfig::mutation_operators`
+pub type WeightedOperator = (f32, Arc<dyn MutationOperator>);
+
+/// Changes the weight of a random connection, or of every
+/// connection with `GaussianWeights`
+pub struct ChangeWeight;
+
+/// Disables a random connection and replaces it with a new
+/// hidden node and two connections
+pub struct SplitConnection;
+
+/// Connects two random nodes which aren't connected yet
+pub struct CreateConnection;
+
+/// Enables or disables a random connection
+pub struct ToggleConnection;
+
+/// Removes a random connection gene
+pub struct DeleteConnection;
+
+/// Removes every connection of a random hidden node
+pub struct DeleteNode;
+
+/// Adds or changes the learning rule of a random connection,
+/// if plasticity is enabled
+pub struct ChangePlasticity;
+
+/// Changes the time constant of a random node, if ctrnn
+/// is enabled
+pub struct ChangeTimeConstant;
+
+impl MutationOperator for ChangeWeight {
+    fn mutate(&self, network: &mut NeatNetwork, rng: &mut ThreadRng, _: &InnovationRegistry) { network.mutate_random_gene_weight(rng) }
+}
+impl MutationOperator for SplitConnection {
+    fn mutate(&self, network: &mut NeatNetwork, rng: &mut ThreadRng, _: &InnovationRegistry) { network.mutate_split_connection(rng) }
+}
+impl MutationOperator for CreateConnection {
+    fn mutate(&self, network: &mut NeatNetwork, rng: &mut ThreadRng, _: &InnovationRegistry) { network.mutate_create_connection(rng) }
+}
+impl MutationOperator for ToggleConnection {
+    fn mutate(&self, network: &mut NeatNetwork, rng: &mut ThreadRng, _: &InnovationRegistry) { network.mutate_toggle_random_gene(rng) }
+}
+impl MutationOperator for DeleteConnection {
+    fn mutate(&self, network: &mut NeatNetwork, rng: &mut ThreadRng, _: &InnovationRegistry) { network.mutate_delete_connection(rng) }
+}
+impl MutationOperator for DeleteNode {
+    fn mutate(&self, network: &mut NeatNetwork, rng: &mut ThreadRng, _: &InnovationRegistry) { network.mutate_delete_node(rng) }
+}
+impl MutationOperator for ChangePlasticity {
+    fn mutate(&self, network: &mut NeatNetwork, rng: &mut ThreadRng, _: &InnovationRegistry) { network.mutate_random_gene_plasticity(rng) }
+}
+impl MutationOperator for ChangeTimeConstant {
+    fn mutate(&self, network: &mut NeatNetwork, rng: &mut ThreadRng, _: &InnovationRegistry) { network.mutate_random_time_constant(rng) }
+}
@@ -0,0 +1,58 @@
+/* Imports */
+use crate::trainer::config::self_adaptation::MutationRates;
+use super::{connection_gene::ConnectionGene, plasticity::HebbianRule};
+
+/// A single change a mutation made to a genome
+#[derive(Clone, Debug)]
+pub enum Change {
+    /// Connection gene `index` got a new weight
+    Weight { index: usize, old: f32, new: f32 },
+
+    /// Connection gene `index` was enabled or disabled
+    Toggle { index: usize, old: bool, new: bool },
+
+    /// A connection gene was pushed, ending up at `index`
+    ConnectionAdded { index: usize },
+
+    /// Connection gene `index` was removed
+    ConnectionRemoved { index: usize, gene: ConnectionGene },
+
+    /// Hidden node `node` was pushed to split connection
+    /// gene `connection`
+    NodeAdded { node: usize, connection: usize },
+
+    /// Connection gene `index` got a new learning rule
+    Plasticity { index: usize, old: Option<HebbianRule>, new: Option<HebbianRule> },
+
+    /// Node gene `node` got a new time constant
+    TimeConstant { node: usize, old: f32, new: f32 },
+}
+
+/// Everything one call to `NeatNetwork::mutate` changed, in
+/// order. Can be kept as a mutation log, or handed back to
+/// `NeatNetwork::revert` to undo the mutation without having
+/// cloned the network beforehand.
+///
+/// ```ignore
+/// let record = network.mutate();
+/// if evaluate(&mut network) < fitness { network.revert(record); }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MutationRecord {
+    pub(crate) changes: Vec<Change>,
+
+    /// What the network looked like before the mutation, for
+    /// the parts which aren't genes
+    pub(crate) mutation_rates: MutationRates,
+    pub(crate) fitness_before_mutation: Option<f32>,
+    pub(crate) highest_local_innovation: usize,
+}
+
+impl MutationRecord {
+    /// True if no gene was changed, e.g the "nothing"
+    /// mutation was picked
+    pub fn is_empty(&self) -> bool { self.changes.is_empty() }
+
+    // Getters
+    pub fn changes(&self) -> &Vec<Change> { &self.changes }
+}
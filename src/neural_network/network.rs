@@ -1,13 +1,18 @@
 /* Imports */
 use std::{collections::{HashMap, HashSet}, fmt::Debug, iter, sync::{Arc, Mutex}};
-use rand::{thread_rng, Rng};
+use rand::{rngs::ThreadRng, thread_rng, Rng};
+use rand_distr::{Distribution, Poisson};
 use serde_derive::{Serialize, Deserialize};
-use crate::trainer::{config::{mutation::GenomeMutationProbablities, network_config::NetworkConfig}, fitness::FitnessEvaluator};
-use super::{activation::NetworkActivations, average::exponential_average, connection_gene::ConnectionGene, node_gene::{NodeGene, NodeGeneType}};
+use crate::trainer::{config::{mutation::{GenomeMutationProbablities, IndependentMutations}, network_config::NetworkConfig, self_adaptation::{Adaptation, MutationRates}}, fitness::FitnessEvaluator};
+use super::{activation::NetworkActivations, mutation_record::{Change, MutationRecord}, mutation_operator::{ChangePlasticity, ChangeTimeConstant, ChangeWeight, CreateConnection, DeleteConnection, DeleteNode, MutationOperator, SplitConnection, ToggleConnection}, average::exponential_average, connection_gene::ConnectionGene, node_gene::{NodeGene, NodeGeneType}, plasticity::HebbianRule};
 
 /* Constants */
 pub const AVERAGE_FITNESS_WINDOW_SIZE: usize = 12;
 
+/// The global innovation number and occupied connections, shared
+/// by every network which can be crossed over with each other
+pub type InnovationRegistry = (Arc<Mutex<usize>>, Arc<Mutex<HashMap<(usize, usize), usize>>>);
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct NeatNetwork {
     /// The amount of neurons to feed in
@@ -51,6 +56,12 @@ pub struct NeatNetwork {
     /// Used for calculating excess nodes in distance function
     highest_local_innovation: usize,
 
+    /// Amount of connection genes touching each node, and how
+    /// many hidden nodes are touched by any. Kept up to date so
+    /// `ComplexityLimits::max_hidden_nodes` is cheap to check
+    node_connections: Vec<usize>,
+    connected_hidden_nodes: usize,
+
     /// The network activation functons for hidden and output
     /// layers (two diffrent)
     activations: NetworkActivations,
@@ -67,6 +78,44 @@ pub struct NeatNetwork {
     #[serde(skip)]
     network_config: Arc<NetworkConfig>,
 
+    /// Behaviour descriptor from the latest evaluation
+    #[serde(skip)]
+    behaviour: Vec<f32>,
+
+    /// How novel `behaviour` was compared to the rest of the
+    /// population and the novelty archive, see `NoveltyArchive`
+    #[serde(skip)]
+    novelty: f32,
+
+    /// Objectives from the latest evaluation, see `Evaluation`
+    #[serde(skip)]
+    objectives: Vec<f32>,
+
+    /// Pareto front (0 = non-dominated) and crowding distance
+    /// within the whole population, see `MultiObjectiveConfig`
+    #[serde(skip)]
+    pareto_rank: usize,
+    #[serde(skip)]
+    crowding_distance: f32,
+
+    /// Ticks since this network was born, see `RealTimeEvolution`
+    #[serde(skip)]
+    age: usize,
+
+    /// Scales the mutation probabilities and step sizes of the
+    /// network config, see `SelfAdaptationConfig`
+    #[serde(skip)]
+    mutation_rates: MutationRates,
+
+    /// Latest fitness when this network was last mutated, so
+    /// the 1/5th success rule can tell if the mutation helped
+    #[serde(skip)]
+    fitness_before_mutation: Option<f32>,
+
+    /// Changes made by the mutation in progress, see `MutationRecord`
+    #[serde(skip)]
+    journal: Vec<Change>,
+
     /// The previous sort of the topology
     topology_sort_cached: Vec<usize>,
 
@@ -140,9 +189,11 @@ impl NeatNetwork {
                     // because it should always be true for initializing
                     // weights
                     local_innovation += 1;
-                    if let Some(conn) = connection { connection_genes.push(conn); };
-                    // Register that we've created a new outgoing weight for the new node
-                    node_genes[output_idx].register_new_incoming(connection_genes.len() - 1);
+                    if let Some(conn) = connection {
+                        connection_genes.push(conn);
+                        // Register that we've created a new outgoing weight for the new node
+                        node_genes[output_idx].register_new_incoming(connection_genes.len() - 1);
+                    };
                 }
             }
         }
@@ -163,28 +214,37 @@ impl NeatNetwork {
             // because it should always be true for initializing
             // weights
             local_innovation += 1;
-            if let Some(conn) = connection { connection_genes.push(conn); };
+            if let Some(conn) = connection {
+                connection_genes.push(conn);
 
-            // Register that we've created a new outgoing weight for the new node
-            node_genes[output_idx].register_new_incoming(connection_genes.len() - 1);
+                // Register that we've created a new outgoing weight for the new node
+                node_genes[output_idx].register_new_incoming(connection_genes.len() - 1);
+            };
         }
 
         // Set the global innovation because the "starter"
         // connection genes. We do -1 because we didn't set
         // a single connection for the last incremented inno.
-        *global_innovation.lock().unwrap() = local_innovation - 1;
+        // Only ever raised, other networks sharing the counter
+        // may already have registered later innovations
+        let mut global = global_innovation.lock().unwrap();
+        *global = (*global).max(local_innovation - 1);
+        drop(global);
 
-        Self {
+        let mut network = Self {
             input_size: input,
             output_size: output,
 
             node_genes,
             connection_genes,
-            node_gene_index: input + output,
+            // Inputs, outputs and the bias node
+            node_gene_index: input + output + 1,
             global_innovation,
             global_occupied_connections,
             local_occupied_connections,
             highest_local_innovation,
+            node_connections: Vec::new(),
+            connected_hidden_nodes: 0,
             activations,
             previous_fitness: 0.,
             average_fitness: 0.,
@@ -194,11 +254,24 @@ impl NeatNetwork {
             // TODO: Should we initialize with sorted or not? I think not
             topology_sort_cached: Vec::new(),
             need_topology_resorted: true,
-        }
+            behaviour: Vec::new(),
+            novelty: 0.0,
+            objectives: Vec::new(),
+            pareto_rank: 0,
+            crowding_distance: 0.0,
+            age: 0,
+            mutation_rates: MutationRates::default(),
+            fitness_before_mutation: None,
+            journal: Vec::new(),
+        };
+        network.count_node_connections();
+        network
     }
 
     /// Create a new network but provide the genes (connections). Used
-    /// after crossing over two parents' genes
+    /// after crossing over two parents' genes. Node types are derived
+    /// from the node indexes (inputs, outputs, bias then hidden), see
+    /// `NetworkBuilder` for building a network node by node.
     pub fn new_with_genes(
         input: usize,
         output: usize,
@@ -208,50 +281,26 @@ impl NeatNetwork {
         connection_genes: Vec<ConnectionGene>,
         network_config: Arc<NetworkConfig>,
     ) -> Self {
-        let mut highest_local_innovation = 0;
-        let mut local_occupied_connections = HashSet::new();
-        let mut highest_node_index = 0;
-        let mut incoming: HashMap<usize, Vec<usize>> = HashMap::new();
-        let mut max_incoming = 0;
-        
+        let mut incoming_count: HashMap<usize, usize> = HashMap::new();
         for connection in &connection_genes {
-            let node_in = connection.node_in();
-            let node_out = connection.node_out();
-
-            match incoming.get_mut(&node_out) {
-                Some(e) => {
-                    e.push(node_in);
-                    if e.len() > max_incoming {
-                        max_incoming = e.len();
-                    }
-                },
-                None => { incoming.insert(node_out, vec![node_in]); }
-            };
-
-            local_occupied_connections.insert((node_in, node_out));
-            if connection.innovation_number() > highest_local_innovation {
-                highest_local_innovation = connection.innovation_number();
-            }
-
-            let local_highest_index = node_in.max(node_out);
-            if local_highest_index > highest_node_index {
-                highest_node_index = local_highest_index;
-            }
+            *incoming_count.entry(connection.node_out()).or_insert(0) += 1;
         }
+        let max_incoming = incoming_count.values().copied().max().unwrap_or(0);
+
+        // There's always room for every input, output and the bias
+        // node, even if no connection gene touches them
+        let highest_node_index = connection_genes.iter()
+            .map(|e| e.node_in().max(e.node_out()))
+            .max()
+            .unwrap_or(0)
+            .max(input + output);
 
         let mut node_genes = Vec::new();
         for i in 0..highest_node_index + 1 {
             /* Bias */
             if i == input + output {
-                let mut node_gene = NodeGene::new(NodeGeneType::Output, 0.0);
+                let mut node_gene = NodeGene::new(NodeGeneType::Input, 0.0);
                 node_gene.set_bias(1.);
-                match incoming.get(&i) {
-                    Some(indexes) => {
-                        node_gene.set_incoming_indexes(indexes.clone());
-                        node_gene.set_x(indexes.len() as f32 / max_incoming as f32 * 0.8 + 0.1);
-                    },
-                    None => {}
-                };
                 node_genes.push(node_gene);
             }else {
                 let node_type = match i {
@@ -267,29 +316,150 @@ impl NeatNetwork {
                     NodeGeneType::Output => 1.0,
                 };
                 let mut node_gene = NodeGene::new(node_type, x);
-                match incoming.get(&i) {
-                    Some(indexes) => {
-                        node_gene.set_incoming_indexes(indexes.clone());
-                        node_gene.set_x(indexes.len() as f32 / max_incoming as f32 * 0.8 + 0.1);
-                    },
-                    None => {}
-                };
+                if let Some(count) = incoming_count.get(&i) {
+                    node_gene.set_x(*count as f32 / max_incoming as f32 * 0.8 + 0.1);
+                }
 
                 node_genes.push(node_gene);
             }
+        }
+
+        Self::from_genes(
+            input, output,
+            node_genes, connection_genes,
+            global_innovation, global_occupied_connections,
+            activations, network_config
+        )
+    }
 
+    /// Get the offspring of this network and `other`. The child
+    /// shares the innovation registry of this network. Biases,
+    /// time constants and activation functions of the nodes come
+    /// from the fitter parent, or the other one if only it has
+    /// the node.
+    pub fn crossover(&self, other: &NeatNetwork, fitness1: f32, fitness2: f32) -> NeatNetwork {
+        let mut offspring = NeatNetwork::new_with_genes(
+            self.input_size(), self.output_size(),
+            self.global_innovation.clone(),
+            self.global_occupied_connections.clone(),
+            self.activations(),
+            self.crossover_genes(other, fitness1, fitness2),
+            self.network_config()
+        );
+
+        let (fitter, other) = if fitness1 >= fitness2 { (self, other) } else { (other, self) };
+        for (index, node_gene) in offspring.node_genes.iter_mut().enumerate() {
+            let Some(parent) = fitter.node_genes.get(index).or_else(|| other.node_genes.get(index)) else { continue };
+            node_gene.set_bias(parent.bias());
+            node_gene.set_time_constant(parent.time_constant());
+            node_gene.set_activation_function(parent.activation_function());
         }
+        offspring.set_mutation_rates(fitter.mutation_rates);
+        offspring
+    }
 
-        Self {
+    /// The connection genes of the offspring of this network and
+    /// `other`. Matching genes are inherited from a random parent,
+    /// disjoint and excess genes from the fitter one.
+    pub fn crossover_genes(&self, other: &NeatNetwork, fitness1: f32, fitness2: f32) -> Vec<ConnectionGene> {
+        let mut rng = thread_rng();
+        let mut child_genes: Vec<ConnectionGene> = Vec::new();
+    
+        let mut i = 0;
+        let mut j = 0;
+        
+        let net1_genes = self.get_genes();
+        let net2_genes = other.get_genes();
+
+        // Traverse both parent genomes
+        while i < net1_genes.len() && j < net2_genes.len() {
+            let gene1 = &net1_genes[i];
+            let gene2 = &net2_genes[j];
+
+            if gene1.innovation_number() == gene2.innovation_number() {
+                // Matching genes: Randomly inherit from either parent
+                if rng.gen() {
+                    child_genes.push(gene1.clone());
+                } else {
+                    child_genes.push(gene2.clone());
+                }
+                i += 1;
+                j += 1;
+            } else {
+                // Disjoint gene from net1_genes
+                if fitness1 >= fitness2 {
+                    child_genes.push(gene1.clone());
+                }else {
+                    child_genes.push(gene2.clone());
+                }
+                i += 1;
+                j += 1;
+            }
+        }
+        
+        // Handle excess genes from the longer genome
+        if fitness1 >= fitness2 {
+            while i < net1_genes.len() {
+                child_genes.push(net1_genes[i].clone());
+                i += 1;
+                j += 1;
+            }
+        } else {
+            while j < net2_genes.len() {
+                child_genes.push(net2_genes[j].clone());
+                i += 1;
+                j += 1;
+            }
+        }
+
+        child_genes
+    }
+
+    /// Create a network from already typed node genes and connection
+    /// genes. The incoming connection indexes of every node, the local
+    /// occupied connections and the highest local innovation are all
+    /// derived from the connection genes.
+    /// 
+    /// Node genes are expected to be ordered as inputs, outputs, the
+    /// bias node and then hidden nodes. Use `validate` to check that.
+    pub fn from_genes(
+        input: usize,
+        output: usize,
+        mut node_genes: Vec<NodeGene>,
+        connection_genes: Vec<ConnectionGene>,
+        global_innovation: Arc<Mutex<usize>>,
+        global_occupied_connections: Arc<Mutex<HashMap<(usize, usize), usize>>>,
+        activations: NetworkActivations,
+        network_config: Arc<NetworkConfig>,
+    ) -> Self {
+        let mut highest_local_innovation = 0;
+        let mut local_occupied_connections = HashSet::new();
+        let mut incoming: HashMap<usize, Vec<usize>> = HashMap::new();
+
+        for (connection_index, connection) in connection_genes.iter().enumerate() {
+            incoming.entry(connection.node_out()).or_default().push(connection_index);
+            local_occupied_connections.insert((connection.node_in(), connection.node_out()));
+            if connection.innovation_number() > highest_local_innovation {
+                highest_local_innovation = connection.innovation_number();
+            }
+        }
+
+        for (index, node_gene) in node_genes.iter_mut().enumerate() {
+            node_gene.set_incoming_indexes(incoming.remove(&index).unwrap_or_default());
+        }
+
+        let mut network = Self {
             input_size: input,
             output_size: output,
+            node_gene_index: node_genes.len(),
             node_genes,
             connection_genes,
-            node_gene_index: highest_node_index + 1,
             global_innovation,
             global_occupied_connections,
             local_occupied_connections,
             highest_local_innovation,
+            node_connections: Vec::new(),
+            connected_hidden_nodes: 0,
             activations,
             previous_fitness: 0.,
             average_fitness: 0.,
@@ -299,73 +469,340 @@ impl NeatNetwork {
             // TODO: Should we initialize with sorted or not? I think not
             topology_sort_cached: Vec::new(),
             need_topology_resorted: true,
+            behaviour: Vec::new(),
+            novelty: 0.0,
+            objectives: Vec::new(),
+            pareto_rank: 0,
+            crowding_distance: 0.0,
+            age: 0,
+            mutation_rates: MutationRates::default(),
+            fitness_before_mutation: None,
+            journal: Vec::new(),
+        };
+        network.count_node_connections();
+        network
+    }
+
+    /// Mutates the network in one of many ways, or several at
+    /// once if `NetworkConfig::independent_mutations` is set.
+    /// Returns what changed, see `revert`
+    pub fn mutate(&mut self) -> MutationRecord {
+        self.journal.clear();
+        let mut record = MutationRecord {
+            changes: Vec::new(),
+            mutation_rates: self.mutation_rates,
+            fitness_before_mutation: self.fitness_before_mutation,
+            highest_local_innovation: self.highest_local_innovation,
+        };
+
+        if let Some(config) = self.network_config.self_adaptation {
+            if let Adaptation::LogNormal { learning_rate } = config.adaptation {
+                self.mutation_rates.mutate(learning_rate, config.min_rate, config.max_rate);
+            }
+        }
+        self.fitness_before_mutation = Some(self.previous_fitness);
+
+        match self.network_config.independent_mutations {
+            Some(config) => self.mutate_independently(&config),
+            None => self.mutate_once(),
+        }
+
+        if let Some(bounds) = self.network_config.weight_bounds {
+            for index in 0..self.connection_genes.len() {
+                self.change_weight(index, |gene| gene.clamp_weight(&bounds));
+            }
         }
+
+        if cfg!(debug_assertions) && self.network_config.validate_after_mutation {
+            if let Err(violations) = self.validate() {
+                panic!("Mutation broke the genome: {violations:?}\n{self:?}");
+            }
+        }
+
+        record.changes = std::mem::take(&mut self.journal);
+        record
     }
 
-    /// Mutates the network in one of many ways
-    pub fn mutate(&mut self) -> () {
+    /// Undoes a mutation. `record` needs to be the latest one
+    /// `mutate` returned for this network. Innovation numbers the
+    /// mutation registered stay taken, like those of any offspring
+    /// which didn't survive.
+    pub fn revert(&mut self, record: MutationRecord) {
+        let mut structural = false;
+        for change in record.changes.into_iter().rev() {
+            match change {
+                Change::Weight { index, old, .. } => self.connection_genes[index].set_weight(old),
+                Change::Toggle { index, old, .. } => {
+                    self.connection_genes[index].set_enabled(old);
+                    self.need_topology_resorted = true;
+                },
+                Change::ConnectionAdded { index } => {
+                    self.connection_genes.remove(index);
+                    structural = true;
+                },
+                Change::ConnectionRemoved { index, gene } => {
+                    self.connection_genes.insert(index, gene);
+                    structural = true;
+                },
+                Change::NodeAdded { node, .. } => {
+                    self.node_genes.remove(node);
+                    self.node_gene_index -= 1;
+                    structural = true;
+                },
+                Change::Plasticity { index, old, .. } => self.connection_genes[index].set_plasticity(old),
+                Change::TimeConstant { node, old, .. } => self.node_genes[node].set_time_constant(old),
+            }
+        }
+
+        if structural {
+            self.rebuild_connection_state();
+            self.highest_local_innovation = record.highest_local_innovation;
+        }
+        self.mutation_rates = record.mutation_rates;
+        self.fitness_before_mutation = record.fitness_before_mutation;
+    }
+
+    /// Applies `change` to connection gene `index` and records
+    /// the weight change, if there was one
+    fn change_weight(&mut self, index: usize, change: impl FnOnce(&mut ConnectionGene)) {
+        let gene = &mut self.connection_genes[index];
+        let old = gene.weight();
+        change(gene);
+        let new = gene.weight();
+        if new != old { self.journal.push(Change::Weight { index, old, new }); }
+    }
+
+    /// Picks one mutation, weighted by `mutation_probabilities`
+    fn mutate_once(&mut self) {
         let mut rng = thread_rng();
         let GenomeMutationProbablities {
             split_connection,
             create_connection,
             change_weight,
             toggle_weight,
+            delete_connection,
+            delete_node,
             nothing
         } = self.network_config.mutation_probabilities;
+        let plasticity = self.network_config.plasticity.map(|p| p.mutation_probability).unwrap_or(0);
+        let time_constant = self.network_config.ctrnn.map(|c| c.mutation_probability).unwrap_or(0);
+        let rates = self.mutation_rates;
+        let config = self.network_config.clone();
+        let registry = self.innovation_registry();
 
-        let probabilities: Vec<(usize, fn(&mut NeatNetwork) -> ())> = vec![
+        let builtin: [(f32, &dyn MutationOperator); 8] = [
             /* Randomly select one gene for mutation */
-            (change_weight, Self::mutate_random_gene_weight),
+            (change_weight as f32 * rates.change_weight, &ChangeWeight),
             
             /* Split connection or create new */
-            (split_connection, Self::mutate_split_connection),
-            (create_connection, Self::mutate_create_connection),
+            (split_connection as f32 * rates.split_connection, &SplitConnection),
+            (create_connection as f32 * rates.create_connection, &CreateConnection),
 
             /* Toggle random connection */
-            (toggle_weight, Self::mutate_toggle_random_gene),
+            (toggle_weight as f32 * rates.toggle_weight, &ToggleConnection),
 
-            /* Mutate nothing */
-            (nothing, |_| {}),
-        ];
+            /* Simplify, remove a connection or a node's connections */
+            (delete_connection as f32 * rates.delete_connection, &DeleteConnection),
+            (delete_node as f32 * rates.delete_node, &DeleteNode),
+
+            /* Change the learning rule of a random connection */
+            (plasticity as f32, &ChangePlasticity),
 
-        let total: usize = probabilities.iter().map(|e| e.0).sum();
-        let random_number = rng.gen_range(0..total);
-        let mut cumulative = 0;
-        for &(probability, func) in probabilities.iter() {
+            /* Change the time constant of a random node */
+            (time_constant as f32, &ChangeTimeConstant),
+        ];
+        let operators: Vec<(f32, &dyn MutationOperator)> = builtin.into_iter()
+            .chain(config.mutation_operators.iter().map(|(weight, operator)| (*weight, operator.as_ref())))
+            .collect();
+
+        /* Mutate nothing if none of the operators is picked */
+        let total: f32 = operators.iter().map(|e| e.0).sum::<f32>() + nothing as f32;
+        let random_number = rng.gen_range(0.0..total);
+        let mut cumulative = 0.0;
+        for (probability, operator) in operators {
             cumulative += probability;
             if random_number < cumulative {
-                (func)(self);
-                //debug
+                operator.mutate(self, &mut rng, &registry);
                 break;
             }
         }
     }
 
-    fn mutate_random_gene_weight(&mut self) -> () {
-        if self.get_genes().len() < 1 { return; };
+    /// Every mutation gets its own roll, structural ones first,
+    /// then a poisson distributed amount of weight mutations
+    fn mutate_independently(&mut self, config: &IndependentMutations) {
         let mut rng = thread_rng();
-        let length = self.connection_genes.len();
-        let gene = &mut self.connection_genes[rng.gen_range(0..length)];
-        gene.mutate_weight(&self.network_config.weight_change_probabilities);
+        let rates = self.mutation_rates;
+
+        let registry = self.innovation_registry();
+        let network_config = self.network_config.clone();
+
+        let builtin: [(f64, &dyn MutationOperator); 7] = [
+            (config.split_connection * rates.split_connection as f64, &SplitConnection),
+            (config.create_connection * rates.create_connection as f64, &CreateConnection),
+            (config.toggle_weight * rates.toggle_weight as f64, &ToggleConnection),
+            (config.delete_connection * rates.delete_connection as f64, &DeleteConnection),
+            (config.delete_node * rates.delete_node as f64, &DeleteNode),
+            (config.plasticity, &ChangePlasticity),
+            (config.time_constant, &ChangeTimeConstant),
+        ];
+        let operators = builtin.into_iter()
+            .chain(network_config.mutation_operators.iter().map(|(probability, operator)| (*probability as f64, operator.as_ref())));
+        for (probability, operator) in operators {
+            if rng.gen_bool(probability.clamp(0.0, 1.0)) {
+                operator.mutate(self, &mut rng, &registry);
+            }
+        }
+
+        let mean = config.weight_mutations * rates.change_weight;
+        let amount = Poisson::new(mean).map(|poisson| poisson.sample(&mut rng) as usize).unwrap_or(0);
+
+        // A gaussian weight mutation already goes over every
+        // connection, so the amount only decides if it happens
+        let amount = if self.network_config.gaussian_weights.is_some() { amount.min(1) } else { amount };
+        for _ in 0..amount {
+            self.mutate_random_gene_weight(&mut rng);
+        }
     }
 
-    fn mutate_toggle_random_gene(&mut self) -> () {
+    pub(crate) fn mutate_random_gene_weight(&mut self, rng: &mut ThreadRng) {
+        if self.get_genes().len() < 1 { return; };
+        let step_size = self.mutation_rates.step_size;
+        if let Some(gaussian) = self.network_config.gaussian_weights {
+            for index in 0..self.connection_genes.len() {
+                self.change_weight(index, |gene| gene.mutate_weight_gaussian(&gaussian, step_size));
+            }
+            return;
+        }
+        let probabilities = self.network_config.weight_change_probabilities;
+        let index = rng.gen_range(0..self.connection_genes.len());
+        self.change_weight(index, |gene| gene.mutate_weight_scaled(&probabilities, step_size));
+    }
+
+    pub(crate) fn mutate_random_gene_plasticity(&mut self, rng: &mut ThreadRng) {
+        if self.get_genes().is_empty() { return; };
+        let Some(config) = self.network_config.plasticity else { return };
+        let index = rng.gen_range(0..self.connection_genes.len());
+        let old = self.connection_genes[index].plasticity();
+        self.connection_genes[index].mutate_plasticity(config.coefficient_step);
+        self.journal.push(Change::Plasticity { index, old, new: self.connection_genes[index].plasticity() });
+    }
+
+    /// Inputs and the bias have no state, so only hidden
+    /// and output nodes are picked
+    pub(crate) fn mutate_random_time_constant(&mut self, rng: &mut ThreadRng) {
+        let Some(config) = self.network_config.ctrnn else { return };
+        let candidates: Vec<usize> = (self.input_size..self.node_genes.len()).filter(|&i| !self.is_bias(i)).collect();
+        if candidates.is_empty() { return; };
+
+        let node = candidates[rng.gen_range(0..candidates.len())];
+        let old = self.node_genes[node].time_constant();
+        let new = (old + rng.gen_range(-config.time_constant_step..config.time_constant_step)).max(config.min_time_constant);
+        self.node_genes[node].set_time_constant(new);
+        self.journal.push(Change::TimeConstant { node, old, new });
+    }
+
+    pub(crate) fn mutate_toggle_random_gene(&mut self, rng: &mut ThreadRng) {
         if self.get_genes().len() < 1 { return; };
-        let mut rng = thread_rng();
         let length = self.connection_genes.len();
-        let gene = &mut self.connection_genes[rng.gen_range(0..length)];
-        gene.set_enabled(rng.gen_bool(0.5));
+        let index = rng.gen_range(0..length);
+        let enable = rng.gen_bool(0.5);
+        let (node_in, node_out) = (self.connection_genes[index].node_in(), self.connection_genes[index].node_out());
+        let old = self.connection_genes[index].enabled();
+        if enable && !old && !self.connection_fits_limits(node_in, node_out) { return }
+        self.connection_genes[index].set_enabled(enable);
+        self.journal.push(Change::Toggle { index, old, new: enable });
+
+        // Disabled genes are left out of the topological sort
+        self.need_topology_resorted = true;
+    }
+
+    pub(crate) fn mutate_delete_connection(&mut self, rng: &mut ThreadRng) {
+        if self.connection_genes.is_empty() { return; };
+        let index = rng.gen_range(0..self.connection_genes.len());
+        let gene = self.connection_genes.remove(index);
+        self.journal.push(Change::ConnectionRemoved { index, gene });
+        self.rebuild_connection_state();
     }
 
-    fn mutate_split_connection(&mut self) -> () {
+    /// Node genes can't be removed since node indexes are what
+    /// innovation numbers are registered by, so the node is
+    /// disconnected instead and stays as an unused gene
+    pub(crate) fn mutate_delete_node(&mut self, rng: &mut ThreadRng) {
+        let hidden_start = self.input_size + self.output_size + 1;
+        let connected: Vec<usize> = (hidden_start..self.node_genes.len())
+            .filter(|&node| self.node_connections.get(node).is_some_and(|&count| count > 0))
+            .collect();
+        if connected.is_empty() { return; };
+
+        // Last to first so the recorded indexes stay valid
+        let node = connected[rng.gen_range(0..connected.len())];
+        for index in (0..self.connection_genes.len()).rev() {
+            let connection = &self.connection_genes[index];
+            if connection.node_in() != node && connection.node_out() != node { continue }
+            let gene = self.connection_genes.remove(index);
+            self.journal.push(Change::ConnectionRemoved { index, gene });
+        }
+        self.rebuild_connection_state();
+    }
+
+    /// Derives the incoming connection indexes, local occupied
+    /// connections and highest local innovation from the
+    /// connection genes again, after some were removed
+    fn rebuild_connection_state(&mut self) {
+        let mut incoming = vec![Vec::new(); self.node_genes.len()];
+        for (index, connection) in self.connection_genes.iter().enumerate() {
+            incoming[connection.node_out()].push(index);
+        }
+        for (node_gene, incoming) in self.node_genes.iter_mut().zip(incoming) {
+            node_gene.set_incoming_indexes(incoming);
+        }
+
+        self.local_occupied_connections = self.connection_genes.iter().map(|c| (c.node_in(), c.node_out())).collect();
+        self.highest_local_innovation = self.connection_genes.iter().map(|c| c.innovation_number()).max().unwrap_or(0);
+        self.need_topology_resorted = true;
+        self.count_node_connections();
+    }
+
+    /// Counts `node_connections` and `connected_hidden_nodes`
+    /// from the connection genes again
+    fn count_node_connections(&mut self) {
+        self.node_connections = vec![0; self.node_genes.len()];
+        for connection in &self.connection_genes {
+            self.node_connections[connection.node_in()] += 1;
+            self.node_connections[connection.node_out()] += 1;
+        }
+        let hidden_start = (self.input_size + self.output_size + 1).min(self.node_connections.len());
+        self.connected_hidden_nodes = self.node_connections[hidden_start..].iter().filter(|&&count| count > 0).count();
+    }
+
+    /// Counts a connection gene `from` -> `to` which was just pushed
+    fn count_connection(&mut self, from: usize, to: usize) {
+        let hidden_start = self.input_size + self.output_size + 1;
+        for node in [from, to] {
+            if node >= self.node_connections.len() { self.node_connections.resize(node + 1, 0); }
+            if node >= hidden_start && self.node_connections[node] == 0 { self.connected_hidden_nodes += 1; }
+            self.node_connections[node] += 1;
+        }
+    }
+
+    pub(crate) fn mutate_split_connection(&mut self, rng: &mut ThreadRng) {
         if self.get_genes().len() < 1 { return; };
-        let mut rng = thread_rng();
-        let current_innovation = self.get_global_innovation();
         let length = self.connection_genes.len();
-            
-        let gene = &mut self.connection_genes[rng.gen_range(0..length)];
-        let gene_node_in = gene.node_in();
-        let gene_node_out = gene.node_out();
+        let gene_index = rng.gen_range(0..length);
+
+        // Splitting adds a hidden node, two connections and
+        // one step of depth to every path trough the gene
+        let limits = self.network_config.limits;
+        let (gene_node_in, gene_node_out) = (self.connection_genes[gene_index].node_in(), self.connection_genes[gene_index].node_out());
+        if limits.max_hidden_nodes.is_some_and(|max| self.connected_hidden_nodes >= max) { return }
+        if limits.max_connections.is_some_and(|max| length + 2 > max) { return }
+        if let Some(max) = limits.max_depth.filter(|_| !self.network_config.recurrent()) {
+            let Some((depth, height)) = self.longest_paths() else { return };
+            if depth[gene_node_in] + height[gene_node_out] + 2 > max { return }
+        }
+
+        let gene = &mut self.connection_genes[gene_index];
 
         let node_in_x = &self.node_genes[gene_node_in].x();
         let node_out_x = &self.node_genes[gene_node_out].x();
@@ -375,47 +812,56 @@ impl NeatNetwork {
             new_x *= 1.05;
         }
 
+        let weight = gene.weight();
+        self.journal.push(Change::Toggle { index: gene_index, old: gene.enabled(), new: false });
         gene.set_enabled(false);
+        self.need_topology_resorted = true;
         self.node_genes.push(NodeGene::new(
             NodeGeneType::Regular,
             new_x
         ));
+        self.journal.push(Change::NodeAdded { node: self.node_gene_index, connection: gene_index });
 
+        // Each connection takes the next innovation number as it
+        // is after the previous one, one of them may already be
+        // known and not use up a number
+        let innovation = self.get_global_innovation() + 1;
         let (input_connection, should_increment_ingoing) = Self::create_connection(
             gene_node_in, self.node_gene_index,
             1.0,
             self.global_occupied_connections.clone(),
             &mut self.local_occupied_connections,
             &mut self.highest_local_innovation,
-            current_innovation + 1,
-            false
+            innovation,
+            self.network_config.recurrent()
         );
+        if should_increment_ingoing { self.increment_global_innovation(); };
+        let innovation = self.get_global_innovation() + 1;
         let (output_connection, should_increment_outgoing) = Self::create_connection(
             self.node_gene_index, gene_node_out,
-            gene.weight(),
+            weight,
             self.global_occupied_connections.clone(),
             &mut self.local_occupied_connections,
             &mut self.highest_local_innovation,
-            current_innovation + 2,
-            false
+            innovation,
+            self.network_config.recurrent()
         );
-
-        // If the genes were actually created we increment the 
-        // innovation number accordingly to match the previous
-        // current_innovation + 1 and + 2
-        if should_increment_ingoing { self.increment_global_innovation(); };
         if should_increment_outgoing { self.increment_global_innovation(); };
 
         // Register that we've created a new incoming weight
         // for the new node, and the updated node and push connection
         if let Some(input) = input_connection {
+            self.count_connection(input.node_in(), input.node_out());
             self.connection_genes.push(input);
-            self.node_genes[self.node_gene_index].register_new_incoming(self.connection_genes.len() - 2);
+            self.node_genes[self.node_gene_index].register_new_incoming(self.connection_genes.len() - 1);
+            self.journal.push(Change::ConnectionAdded { index: self.connection_genes.len() - 1 });
             self.need_topology_resorted = true;
         };
         if let Some(output) = output_connection {
+            self.count_connection(output.node_in(), output.node_out());
             self.connection_genes.push(output);
             self.node_genes[gene_node_out].register_new_incoming(self.connection_genes.len() - 1);
+            self.journal.push(Change::ConnectionAdded { index: self.connection_genes.len() - 1 });
             self.need_topology_resorted = true;
         };
 
@@ -423,10 +869,8 @@ impl NeatNetwork {
     }
 
     /// Create a random connection
-    fn mutate_create_connection(&mut self) -> () {
-        let mut rng = thread_rng();
-        let current_innovation = self.get_global_innovation();
-
+    pub(crate) fn mutate_create_connection(&mut self, rng: &mut ThreadRng) {
+        if self.network_config.recurrent() { return self.mutate_create_recurrent_connection(rng) }
         self.sort_topology();
         let topology_sorted = &self.topology_sort_cached;
         let mut node_from_idx = rng.gen_range(0..topology_sorted.len() - 1);
@@ -452,24 +896,73 @@ impl NeatNetwork {
 
         // We don't want to have connection 5-3 and create 3-5
         if self.local_occupied_connections.get(&(node_to, node_from)).is_some() { return }
+        if !self.connection_fits_limits(node_from, node_to) { return }
+
+        let weight = rng.gen_range(0.0..1.0);
+        self.push_connection(node_from, node_to, weight);
+    }
+
+    /// Create a random connection between any two nodes, which
+    /// may go back to an earlier node or loop on a node itself
+    fn mutate_create_recurrent_connection(&mut self, rng: &mut ThreadRng) {
+        let nodes = self.node_genes.len();
+        let mut attempts = 0;
+        let (node_from, node_to) = loop {
+            if attempts > 20 { return };
+            attempts += 1;
 
+            let (from, to) = (rng.gen_range(0..nodes), rng.gen_range(0..nodes));
+            if self.is_output(from) || self.is_input(to) || self.is_bias(to) { continue; }
+            break (from, to);
+        };
+
+        if self.local_occupied_connections.contains(&(node_from, node_to)) { return }
+        if !self.connection_fits_limits(node_from, node_to) { return }
+
+        let weight = rng.gen_range(0.0..1.0);
+        self.push_connection(node_from, node_to, weight);
+    }
+
+    /// Adds an enabled connection `from` -> `to`, with the innovation
+    /// number the population already uses for it if there is one.
+    /// False if it already exists, starts at an output node, ends at
+    /// an input or the bias node, creates a cycle (unless the config
+    /// is recurrent) or breaks the complexity limits.
+    pub fn add_connection(&mut self, from: usize, to: usize, weight: f32) -> bool {
+        let nodes = self.node_genes.len();
+        let recurrent = self.network_config.recurrent();
+        if from >= nodes || to >= nodes || (from == to && !recurrent) { return false }
+        if self.is_output(from) || self.is_input(to) || self.is_bias(to) { return false }
+
+        let connection = (from, to);
+        if !recurrent && Self::has_cycle(self.local_occupied_connections.iter().chain(iter::once(&connection))) { return false }
+        if !self.connection_fits_limits(from, to) { return false }
+        self.push_connection(from, to, weight)
+    }
+
+    /// Creates the connection gene and registers its innovation,
+    /// false if the connection already exists
+    fn push_connection(&mut self, node_from: usize, node_to: usize, weight: f32) -> bool {
+        let current_innovation = self.get_global_innovation();
         let (connection, should_increment) = Self::create_connection(
             node_from, node_to,
-            rng.gen_range(0.0..1.0),
+            weight,
             self.global_occupied_connections.clone(),
             &mut self.local_occupied_connections,
             &mut self.highest_local_innovation,
             current_innovation + 1,
-            false
+            self.network_config.recurrent()
         );
 
         // Increase innovation to match the previous self.get_global_innovation() + 1
         if should_increment { self.increment_global_innovation(); };
-        if let Some(conn) = connection {
-            self.connection_genes.push(conn);
-            self.node_genes[node_to].register_new_incoming(self.connection_genes.len() - 1);
-            self.need_topology_resorted = true;
-        };
+        let Some(conn) = connection else { return false };
+        self.count_connection(node_from, node_to);
+        self.connection_genes.push(conn);
+        self.node_genes[node_to].register_new_incoming(self.connection_genes.len() - 1);
+        self.journal.push(Change::ConnectionAdded { index: self.connection_genes.len() - 1 });
+        self.need_topology_resorted = true;
+        true
     }
 
     /// Tries to create a new connection. If the connection already
@@ -546,8 +1039,10 @@ impl NeatNetwork {
     pub fn calculate_output(&mut self, input: Vec<f32>) -> Vec<f32> {
         assert!(input.len() == self.input_size);
         for (index, node_gene) in self.node_genes.iter_mut().enumerate() {
-            // Not for bias node which should be 1.0
-            if index != self.input_size + self.output_size {
+            // The bias node always outputs its bias (1.0)
+            if index == self.input_size + self.output_size {
+                node_gene.set_activation(node_gene.bias());
+            }else {
                 node_gene.set_activation(0.0);
             }
         }
@@ -572,13 +1067,14 @@ impl NeatNetwork {
                 if !connection.enabled() { continue; };
 
                 let prev_node = &self.node_genes[connection.node_in()];
-                sum += prev_node.activation() * connection.weight();
+                sum += prev_node.activation() * connection.effective_weight();
             }
 
             let activated_sum;
             match node.node_type() {
                 NodeGeneType::Regular => {
-                    activated_sum = self.activations.hidden.run(&vec![sum], 0);
+                    let activation = node.activation_function().unwrap_or(self.activations.hidden);
+                    activated_sum = activation.run(&vec![sum], 0);
                 },
                 NodeGeneType::Output => {
                     // We won't activate the output nodes here yet, as we will
@@ -590,6 +1086,15 @@ impl NeatNetwork {
             self.node_genes[*index].set_activation(activated_sum);
         }
         
+        // Plastic connections learn from this pass. Outputs are
+        // seen before the output activation is applied
+        for connection in self.connection_genes.iter_mut() {
+            if !connection.enabled() || connection.plasticity().is_none() { continue; };
+            let pre = self.node_genes[connection.node_in()].activation();
+            let post = self.node_genes[connection.node_out()].activation();
+            connection.learn(pre, post);
+        }
+
         let outputs: Vec<f32> = self.node_genes[self.input_size..(self.input_size + self.output_size)]
             .iter().map(|e| e.activation()).collect();
     
@@ -597,17 +1102,83 @@ impl NeatNetwork {
         (0..outputs.len()).map(|i| self.activations.output.run(&outputs, i)).collect()
     }
 
+    /// Restores every plastic connection to its evolved weight,
+    /// should be called between episodes. `evaluate_fitness`
+    /// does this before every evaluation.
+    pub fn reset_plasticity(&mut self) {
+        for connection in self.connection_genes.iter_mut() {
+            connection.reset_plasticity();
+        }
+    }
+
+    /// Sets the learning rule of the connection gene at `index`
+    pub fn set_plasticity(&mut self, index: usize, plasticity: Option<HebbianRule>) {
+        self.connection_genes[index].set_plasticity(plasticity);
+    }
+
+    /// The weights of all enabled connections followed by the biases
+    /// of all hidden and output nodes, in gene order. Used by
+    /// optimizers which treat the network as a flat vector.
+    pub fn parameter_vector(&self) -> Vec<f32> {
+        self.connection_genes.iter().filter(|c| c.enabled()).map(|c| c.weight())
+            .chain(self.node_genes.iter().enumerate().filter(|(i, _)| self.has_trainable_bias(*i)).map(|(_, n)| n.bias()))
+            .collect()
+    }
+
+    /// Inverse of `parameter_vector`
+    pub fn set_parameter_vector(&mut self, parameters: &[f32]) {
+        let mut parameters = parameters.iter();
+        for connection in self.connection_genes.iter_mut().filter(|c| c.enabled()) {
+            connection.set_weight(*parameters.next().expect("Too few parameters"));
+        }
+        for index in 0..self.node_genes.len() {
+            if !self.has_trainable_bias(index) { continue; }
+            self.node_genes[index].set_bias(*parameters.next().expect("Too few parameters"));
+        }
+        assert!(parameters.next().is_none(), "Too many parameters");
+    }
+
+    /// Hidden and output nodes, inputs and the bias don't use their bias
+    fn has_trainable_bias(&self, index: usize) -> bool {
+        index >= self.input_size && !self.is_bias(index)
+    }
+
+    /// Sets the CTRNN time constant of the node gene at `index`
+    pub fn set_time_constant(&mut self, index: usize, time_constant: f32) {
+        self.node_genes[index].set_time_constant(time_constant);
+    }
+
+    /// If any connection has a learning rule
+    pub fn is_plastic(&self) -> bool {
+        self.connection_genes.iter().any(|c| c.plasticity().is_some())
+    }
+
     /// Will sort the topology, or not if already done
     pub fn sort_topology(&mut self) -> () {
         if self.need_topology_resorted {
             self.need_topology_resorted = false;
-            self.topology_sort_cached = self.generate_topological_sort().unwrap()
+            // Recurrent networks have no order, the nodes are
+            // then just evaluated by index
+            self.topology_sort_cached = match self.generate_topological_sort() {
+                Some(sorted) => sorted,
+                None if self.network_config.recurrent() => (0..self.node_genes.len()).collect(),
+                None => panic!("The network has a cycle but its config isn't recurrent"),
+            }
         }
     }
 
     /// The degree of a node is the amount of weights which are connected to it. And the
     /// indegree is the number of weights coming in, and outdegree are the number going out.
     fn generate_topological_sort(&mut self) -> Option<Vec<usize>> {
+        let sorted = self.topological_sort()?;
+        self.topology_sort_cached = sorted.clone();
+        Some(sorted)
+    }
+
+    /// Returns the order in which node genes are evaluated, following
+    /// enabled connections only. Same order as `calculate_output` uses
+    /// but without touching the cache. None if the graph has a cycle.
+    pub fn topological_sort(&self) -> Option<Vec<usize>> {
         let num_nodes = self.node_genes.len();
         let mut in_degree = vec![0; num_nodes];
         let mut adj_list = vec![vec![]; num_nodes];
@@ -634,7 +1205,6 @@ impl NeatNetwork {
         }
 
         if sorted.len() == num_nodes {
-            self.topology_sort_cached = sorted.clone();
             Some(sorted)
         } else {
             // Graph has a cycle, no valid topological sort
@@ -642,6 +1212,54 @@ impl NeatNetwork {
         }
     }
 
+    /// Most connections on any enabled path from a node without
+    /// incoming connections to one without outgoing ones. A
+    /// network with only direct connections has depth 1.
+    pub fn depth(&self) -> usize {
+        self.longest_paths().map(|(depth, _)| depth.into_iter().max().unwrap_or(0)).unwrap_or(0)
+    }
+
+    /// (depth, height) of every node over enabled connections, the
+    /// most connections on a path into and out of the node. None
+    /// if the graph has a cycle.
+    fn longest_paths(&self) -> Option<(Vec<usize>, Vec<usize>)> {
+        let order = self.topological_sort()?;
+        let mut depth = vec![0; self.node_genes.len()];
+        let mut height = vec![0; self.node_genes.len()];
+        let mut position = vec![0; self.node_genes.len()];
+        for (index, &node) in order.iter().enumerate() { position[node] = index; }
+
+        // Relaxing connections in topological order of their source
+        let mut by_source: Vec<&ConnectionGene> = self.connection_genes.iter().filter(|c| c.enabled()).collect();
+        by_source.sort_by_key(|c| position[c.node_in()]);
+        for connection in by_source.iter() {
+            depth[connection.node_out()] = depth[connection.node_out()].max(depth[connection.node_in()] + 1);
+        }
+        for connection in by_source.iter().rev() {
+            height[connection.node_in()] = height[connection.node_in()].max(height[connection.node_out()] + 1);
+        }
+        Some((depth, height))
+    }
+
+    /// Whether an enabled connection `from` -> `to` stays within
+    /// the connection and depth limits of the network config
+    fn connection_fits_limits(&self, from: usize, to: usize) -> bool {
+        let limits = self.network_config.limits;
+        if limits.max_connections.is_some_and(|max| !self.local_occupied_connections.contains(&(from, to)) && self.connection_genes.len() >= max) {
+            return false;
+        }
+        match limits.max_depth.filter(|_| !self.network_config.recurrent()) {
+            Some(max) => self.longest_paths().is_some_and(|(depth, height)| depth[from] + 1 + height[to] <= max),
+            None => true,
+        }
+    }
+
+    /// If the connections form a cycle although the config
+    /// isn't recurrent, e.g after crossover
+    pub fn has_forbidden_cycle(&self) -> bool {
+        !self.network_config.recurrent() && Self::has_cycle(self.local_occupied_connections.iter())
+    }
+
     pub fn has_cycle<'a, I>(connections: I) -> bool 
     where I: Iterator<Item = &'a (usize, usize)> {
         let mut adj_list: HashMap<usize, Vec<usize>> = HashMap::new();
@@ -724,6 +1342,9 @@ impl NeatNetwork {
         *inno += 1;
         *inno
     }
+    pub fn innovation_registry(&self) -> InnovationRegistry {
+        (self.global_innovation.clone(), self.global_occupied_connections.clone())
+    }
     pub fn get_global_innovation(&self) -> usize {
         *self.global_innovation.lock().unwrap()
     }
@@ -739,26 +1360,66 @@ impl NeatNetwork {
     /// Returns true if the `index` is between
     /// self.input and self.output
     pub fn is_output(&self, index: usize) -> bool {
-        index > self.input_size - 1 && index <= (self.input_size + self.output_size - 1)
+        index >= self.input_size && index < (self.input_size + self.output_size)
     }
     /// Returns true if the `index` is between
     /// 0 and self.input
     pub fn is_input(&self, index: usize) -> bool {
         index < self.input_size
     }
+    /// Returns true if `index` is the bias node, which
+    /// is placed right after the output nodes
     pub fn is_bias(&self, index: usize) -> bool {
-        index == self.input_size
+        index == self.input_size + self.output_size
     }
 
     // Getters
     pub fn input_size(&self) -> usize { self.input_size }
     pub fn output_size(&self) -> usize { self.output_size }
     pub fn node_genes(&self) -> &Vec<NodeGene> { &self.node_genes }
+    pub(crate) fn node_genes_mut(&mut self) -> &mut Vec<NodeGene> { &mut self.node_genes }
+    pub(crate) fn connection_genes_mut(&mut self) -> &mut Vec<ConnectionGene> { &mut self.connection_genes }
     pub fn previous_fitness(&self) -> f32 { self.previous_fitness }
     pub fn activations(&self) -> NetworkActivations { self.activations }
     pub fn local_occupied_connections(&self) -> &HashSet<(usize, usize)> { &self.local_occupied_connections }
     pub fn network_config(&self) -> Arc<NetworkConfig> { self.network_config.clone() }
 
+    /// Mutate with another config from now on, e.g after
+    /// retrieving or moving the network to another evolution
+    pub fn set_network_config(&mut self, network_config: Arc<NetworkConfig>) { self.network_config = network_config; }
+    pub fn node_gene_index(&self) -> usize { self.node_gene_index }
+
+    /// Hidden nodes which any connection gene touches
+    pub fn connected_hidden_nodes(&self) -> usize { self.connected_hidden_nodes }
+    pub fn behaviour(&self) -> &Vec<f32> { &self.behaviour }
+    pub fn novelty(&self) -> f32 { self.novelty }
+    pub fn set_novelty(&mut self, novelty: f32) { self.novelty = novelty; }
+    pub fn objectives(&self) -> &Vec<f32> { &self.objectives }
+    pub fn age(&self) -> usize { self.age }
+    pub fn set_age(&mut self, age: usize) { self.age = age; }
+    pub fn mutation_rates(&self) -> MutationRates { self.mutation_rates }
+    pub fn set_mutation_rates(&mut self, rates: MutationRates) { self.mutation_rates = rates; }
+    pub fn fitness_before_mutation(&self) -> Option<f32> { self.fitness_before_mutation }
+    pub(crate) fn take_fitness_before_mutation(&mut self) -> Option<f32> { self.fitness_before_mutation.take() }
+    pub fn pareto_rank(&self) -> usize { self.pareto_rank }
+    pub fn crowding_distance(&self) -> f32 { self.crowding_distance }
+    pub fn set_pareto(&mut self, rank: usize, crowding_distance: f32) {
+        self.pareto_rank = rank;
+        self.crowding_distance = crowding_distance;
+    }
+
+    /// NSGA-II selection order as a single score: lower fronts
+    /// first, then the less crowded networks within a front
+    pub fn pareto_score(&self) -> f32 {
+        -(self.pareto_rank as f32) - 0.5 / (1.0 + self.crowding_distance)
+    }
+
+    /// The cached topological sort, None if it
+    /// needs to be resorted before being used
+    pub fn cached_topological_sort(&self) -> Option<&Vec<usize>> {
+        if self.need_topology_resorted { None } else { Some(&self.topology_sort_cached) }
+    }
+
     /// Returns the average fitness of the previous 
     /// `AVERAGE_FITNESS_WINDOW_SIZE` nr of evaluations
     pub fn average_fitness(&mut self) -> f32 {
@@ -774,9 +1435,18 @@ impl NeatNetwork {
     pub fn evaluate_fitness<F: FitnessEvaluator>(&mut self, fitness_evaluator: Arc<Mutex<F>>) -> () {
         // TODO WHY do we put this here?
         self.sort_topology();
+        self.reset_plasticity();
 
         // let score = (fitness_func)(self);
-        let score = fitness_evaluator.lock().unwrap().run(self);
+        let evaluation = fitness_evaluator.lock().unwrap().evaluate(self);
+        self.behaviour = evaluation.behaviour;
+        self.objectives = evaluation.objectives;
+        self.record_fitness(evaluation.fitness);
+    }
+
+    /// Store a fitness which was measured outside of a fitness
+    /// evaluator, e.g while living in a simulation
+    pub fn record_fitness(&mut self, score: f32) {
         self.previous_fitness = score;
 
         // Set new average
@@ -1,4 +1,5 @@
 use serde_derive::{Serialize, Deserialize};
+use super::activation::Activation;
 
 /// Input nodes are the ones that recieve data first, 
 /// then nodes will propagate data forward until it
@@ -28,7 +29,19 @@ pub struct NodeGene {
     incoming_connection_indexes: Vec<usize>,
 
     /// Debug visualization. X is a value between 0 and 1
-    x: f32
+    x: f32,
+
+    /// Overrides the hidden activation of the network for this
+    /// node. None = use `NetworkActivations::hidden`
+    activation_function: Option<Activation>,
+
+    /// How slowly the state of this node follows its input
+    /// when the network is run as a CTRNN (`NeatNetwork::step`)
+    time_constant: f32,
+
+    /// CTRNN state, kept between steps
+    #[serde(skip)]
+    state: f32,
 }
 
 impl NodeGene {
@@ -38,7 +51,10 @@ impl NodeGene {
             node_type,
             activation: 0.,
             incoming_connection_indexes: Vec::new(),
-            x
+            x,
+            activation_function: None,
+            time_constant: 1.0,
+            state: 0.0,
         }
     }
 
@@ -49,11 +65,17 @@ impl NodeGene {
     pub fn activation(&self) -> f32 { self.activation }
     pub fn incoming_connection_indexes(&self) -> &Vec<usize> { &self.incoming_connection_indexes }
     pub fn is_indegree_zero(&self) -> bool { self.incoming_connection_indexes.is_empty() }
+    pub fn activation_function(&self) -> Option<Activation> { self.activation_function }
+    pub fn time_constant(&self) -> f32 { self.time_constant }
+    pub fn state(&self) -> f32 { self.state }
 
     // Setters
     pub fn set_activation(&mut self, to: f32) -> () { self.activation = to; }
     pub fn set_x(&mut self, to: f32) -> () { self.x = to; }
     pub fn set_bias(&mut self, to: f32) -> () { self.bias = to; }
+    pub fn set_activation_function(&mut self, to: Option<Activation>) -> () { self.activation_function = to; }
+    pub fn set_time_constant(&mut self, to: f32) { self.time_constant = to; }
+    pub fn set_state(&mut self, to: f32) { self.state = to; }
 
     /// Appends a new incoming connection gene to the list
     pub fn register_new_incoming(&mut self, index: usize) -> () {
@@ -0,0 +1,59 @@
+/* Imports */
+use rand::{thread_rng, Rng};
+use serde_derive::{Serialize, Deserialize};
+
+/* Constants */
+/// The weight of a plastic connection can't drift further
+/// than this from its evolved weight during an episode
+pub const PLASTIC_DELTA_LIMIT: f32 = 5.0;
+
+/// The generalized (ABCD) Hebbian learning rule. Every time the
+/// network is run, a plastic connection changes its weight by
+///
+/// `learning_rate * (a * pre * post + b * pre + c * post + d)`
+///
+/// where `pre` and `post` are the activations of the node the
+/// connection comes from and goes to. All coefficients are
+/// evolved like weights.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct HebbianRule {
+    pub learning_rate: f32,
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+}
+
+impl HebbianRule {
+    pub fn new(learning_rate: f32, a: f32, b: f32, c: f32, d: f32) -> Self {
+        Self { learning_rate, a, b, c, d }
+    }
+
+    /// How much the weight changes for some pre- and
+    /// post-synaptic activation
+    pub fn delta(&self, pre: f32, post: f32) -> f32 {
+        self.learning_rate * (self.a * pre * post + self.b * pre + self.c * post + self.d)
+    }
+
+    /// Shifts one random coefficient by at most `step`
+    pub fn mutate(&mut self, step: f32) {
+        let mut rng = thread_rng();
+        let change = rng.gen_range(-step..step);
+        match rng.gen_range(0..5) {
+            0 => self.learning_rate += change,
+            1 => self.a += change,
+            2 => self.b += change,
+            3 => self.c += change,
+            _ => self.d += change,
+        }
+    }
+}
+
+/// Plain hebbian learning (a = 1) with a small learning rate,
+/// which is what connections start out with when they first
+/// become plastic
+impl Default for HebbianRule {
+    fn default() -> Self {
+        Self { learning_rate: 0.1, a: 1.0, b: 0.0, c: 0.0, d: 0.0 }
+    }
+}
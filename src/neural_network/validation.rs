@@ -0,0 +1,173 @@
+/* Imports */
+use std::collections::HashSet;
+use super::{network::NeatNetwork, node_gene::NodeGeneType};
+
+/// An invariant of `NeatNetwork` which doesn't hold, see
+/// `NeatNetwork::validate`
+#[derive(Clone, PartialEq, Debug)]
+pub enum Violation {
+    /// There are fewer node genes than inputs + outputs + bias
+    MissingNodes { expected: usize, found: usize },
+
+    /// A connection gene points to a node gene which doesn't exist
+    ConnectionOutOfBounds { connection: usize, node: usize },
+
+    /// A node lists an incoming connection index which doesn't exist
+    IncomingOutOfBounds { node: usize, connection: usize },
+
+    /// The incoming connection indexes of a node aren't the
+    /// connection genes which end in that node
+    IncomingMismatch { node: usize, listed: Vec<usize>, actual: Vec<usize> },
+
+    /// Two connection genes connect the same two nodes
+    DuplicateConnection { node_in: usize, node_out: usize },
+
+    /// `local_occupied_connections` isn't the set of (node_in, node_out)
+    /// pairs of the connection genes
+    OccupiedConnectionsMismatch { missing: Vec<(usize, usize)>, extra: Vec<(usize, usize)> },
+
+    /// `highest_local_innovation` isn't the highest innovation
+    /// number of the connection genes
+    HighestInnovationMismatch { stored: usize, actual: usize },
+
+    /// `node_gene_index` isn't the index the next node gene gets
+    NodeGeneIndexMismatch { stored: usize, actual: usize },
+
+    /// A node gene has the wrong type for its position
+    NodeTypeMismatch { node: usize, expected: NodeGeneType, found: NodeGeneType },
+
+    /// The bias node doesn't have a bias of 1.0
+    BiasValue { found: f32 },
+
+    /// A connection ends in an input node or the bias node
+    ConnectionIntoInput { connection: usize },
+
+    /// A connection starts in an output node
+    ConnectionFromOutput { connection: usize },
+
+    /// The enabled connections contain a cycle, and the
+    /// config isn't recurrent (see `CtrnnConfig::recurrent`)
+    Cycle,
+
+    /// The topological sort is marked as up to date but
+    /// doesn't match the current connection genes
+    StaleTopologySort,
+}
+
+impl NeatNetwork {
+    /// Checks that all of the redundant state stored in the network
+    /// (incoming connection indexes, occupied connections, innovation
+    /// and node counters, the cached topological sort) agrees with the
+    /// node and connection genes, and that node types and the bias
+    /// node are where they should be.
+    /// 
+    /// Returns every violation found, not only the first one.
+    pub fn validate(&self) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        let node_genes = self.node_genes();
+        let connection_genes = self.get_genes();
+        let bias_index = self.input_size() + self.output_size();
+
+        if node_genes.len() <= bias_index {
+            violations.push(Violation::MissingNodes { expected: bias_index + 1, found: node_genes.len() });
+        }
+        if self.node_gene_index() != node_genes.len() {
+            violations.push(Violation::NodeGeneIndexMismatch { stored: self.node_gene_index(), actual: node_genes.len() });
+        }
+
+        // Node types and the bias node
+        for (index, node) in node_genes.iter().enumerate() {
+            let expected = match index {
+                _ if self.is_input(index) || index == bias_index => NodeGeneType::Input,
+                _ if self.is_output(index) => NodeGeneType::Output,
+                _ => NodeGeneType::Regular,
+            };
+            if node.node_type() != expected {
+                violations.push(Violation::NodeTypeMismatch { node: index, expected, found: node.node_type() });
+            }
+            if index == bias_index && node.bias() != 1.0 {
+                violations.push(Violation::BiasValue { found: node.bias() });
+            }
+        }
+
+        // Connection genes
+        let mut pairs = HashSet::new();
+        let mut actual_incoming = vec![Vec::new(); node_genes.len()];
+        let mut highest_innovation = 0;
+        for (index, conn) in connection_genes.iter().enumerate() {
+            let (node_in, node_out) = (conn.node_in(), conn.node_out());
+            highest_innovation = highest_innovation.max(conn.innovation_number());
+
+            if !pairs.insert((node_in, node_out)) {
+                violations.push(Violation::DuplicateConnection { node_in, node_out });
+            }
+            for node in [node_in, node_out] {
+                if node >= node_genes.len() {
+                    violations.push(Violation::ConnectionOutOfBounds { connection: index, node });
+                }
+            }
+            if node_out < node_genes.len() {
+                actual_incoming[node_out].push(index);
+            }
+            if self.is_input(node_out) || self.is_bias(node_out) {
+                violations.push(Violation::ConnectionIntoInput { connection: index });
+            }
+            if self.is_output(node_in) {
+                violations.push(Violation::ConnectionFromOutput { connection: index });
+            }
+        }
+
+        if self.get_highest_local_innovation() != highest_innovation {
+            violations.push(Violation::HighestInnovationMismatch {
+                stored: self.get_highest_local_innovation(),
+                actual: highest_innovation
+            });
+        }
+
+        let occupied = self.local_occupied_connections();
+        let mut missing: Vec<(usize, usize)> = pairs.difference(occupied).copied().collect();
+        let mut extra: Vec<(usize, usize)> = occupied.difference(&pairs).copied().collect();
+        if !missing.is_empty() || !extra.is_empty() {
+            missing.sort();
+            extra.sort();
+            violations.push(Violation::OccupiedConnectionsMismatch { missing, extra });
+        }
+
+        // Incoming connection indexes
+        for (index, node) in node_genes.iter().enumerate() {
+            let listed = node.incoming_connection_indexes();
+            for &connection in listed {
+                if connection >= connection_genes.len() {
+                    violations.push(Violation::IncomingOutOfBounds { node: index, connection });
+                }
+            }
+
+            let mut sorted = listed.clone();
+            sorted.sort();
+            if sorted != actual_incoming[index] {
+                violations.push(Violation::IncomingMismatch {
+                    node: index,
+                    listed: listed.clone(),
+                    actual: actual_incoming[index].clone()
+                });
+            }
+        }
+
+        // Topology. The sort can only be computed if every
+        // connection points to an existing node. Recurrent
+        // networks may have cycles and then have no sort.
+        if !violations.iter().any(|v| matches!(v, Violation::ConnectionOutOfBounds { .. })) {
+            match self.topological_sort() {
+                None if self.network_config().recurrent() => {},
+                None => violations.push(Violation::Cycle),
+                Some(sorted) => if let Some(cached) = self.cached_topological_sort() {
+                    if *cached != sorted {
+                        violations.push(Violation::StaleTopologySort);
+                    }
+                },
+            }
+        }
+
+        if violations.is_empty() { Ok(()) } else { Err(violations) }
+    }
+}
@@ -0,0 +1,141 @@
+/* Imports */
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+use rand::{thread_rng, Rng};
+use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
+use crate::neural_network::network::NeatNetwork;
+use super::{config::migration::{MigrationConfig, MigrationTopology}, evolution::{Evolution, EvolutionBuilder}, fitness::FitnessEvaluator};
+
+/// Island model. Several evolutions (islands), each with its
+/// own config, evolve in parallel and every `interval`
+/// generations the best networks of each island migrate to
+/// other islands and replace the worst networks there.
+///
+/// All islands share one innovation registry, so the same
+/// structural change has the same innovation number on every
+/// island and migrants still align when crossing over.
+///
+/// ```ignore
+/// let mut archipelago = Archipelago::new(MigrationConfig::default());
+/// for _ in 0..4 {
+///     archipelago.add_island(Evolution::new().batch_size(10).with_input_nodes(2).with_output_nodes(1).set_fitness_evaluator(evaluator));
+/// }
+/// for _ in 0..100 { archipelago.generation(); }
+/// ```
+pub struct Archipelago<F: FitnessEvaluator + Send + Sync> {
+    islands: Vec<Evolution<F>>,
+    config: MigrationConfig,
+    generation: usize,
+
+    global_innovation_number: Arc<Mutex<usize>>,
+    global_occupied_connections: Arc<Mutex<HashMap<(usize, usize), usize>>>,
+}
+
+impl<F: FitnessEvaluator + Send + Sync> Archipelago<F> {
+    pub fn new(config: MigrationConfig) -> Self {
+        Self {
+            islands: Vec::new(),
+            config,
+            generation: 0,
+            global_innovation_number: Arc::new(Mutex::new(0)),
+            global_occupied_connections: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Adds an island, configured like any other evolution.
+    /// Its innovation registry is set here. Panics if its networks
+    /// don't have the same inputs and outputs as the other islands,
+    /// as migrants couldn't run there.
+    pub fn add_island(&mut self, builder: &mut EvolutionBuilder<F>) -> &mut Self {
+        builder.innovation_registry(self.global_innovation_number.clone(), self.global_occupied_connections.clone());
+        let island = builder.build();
+        if let (Some(first), Some(new)) = (self.islands.first().and_then(sizes), sizes(&island)) {
+            assert!(
+                first == new,
+                "Island {} has {} inputs and {} outputs, but the other islands have {} and {}",
+                self.islands.len(), new.0, new.1, first.0, first.1
+            );
+        }
+        self.islands.push(island);
+        self
+    }
+
+    /// Runs a generation on every island in parallel, then
+    /// migrates if it's time to. Returns true if any island
+    /// wants to stop.
+    pub fn generation(&mut self) -> bool {
+        assert!(!self.islands.is_empty(), "Archipelago needs at least one island");
+        self.generation += 1;
+
+        let should_stop = self.islands.par_iter_mut()
+            .map(|island| island.generation())
+            .reduce(|| false, |a, b| a || b);
+
+        if self.config.interval > 0 && self.generation.is_multiple_of(self.config.interval) {
+            self.migrate();
+        }
+        should_stop
+    }
+
+    /// Sends the best networks of every island to its
+    /// destinations (see `MigrationTopology`)
+    pub fn migrate(&mut self) {
+        let amount = self.islands.len();
+        if amount < 2 { return }
+
+        let mut rng = thread_rng();
+
+        // Ranked once, so migrants which just arrived aren't
+        // replaced by the migrants of another source
+        let mut free_slots: Vec<std::vec::IntoIter<(usize, usize)>> = self.islands.iter().map(|island| ranked(island).into_iter()).collect();
+        let emigrants: Vec<Vec<NeatNetwork>> = self.islands.iter()
+            .map(|island| ranked(island).into_iter().rev().take(self.config.migrants).map(|(s, n)| island.species()[s].networks()[n].clone()).collect())
+            .collect();
+
+        for (source, migrants) in emigrants.iter().enumerate() {
+            let destinations: Vec<usize> = match self.config.topology {
+                MigrationTopology::Ring => vec![(source + 1) % amount],
+                MigrationTopology::FullyConnected => (0..amount).filter(|&i| i != source).collect(),
+                MigrationTopology::Random => vec![(source + rng.gen_range(1..amount)) % amount],
+            };
+
+            for destination in destinations {
+                let island = &mut self.islands[destination];
+                let network_config = island.species()[0].networks()[0].network_config();
+                for (migrant, (s, n)) in migrants.iter().zip(free_slots[destination].by_ref()) {
+                    let mut migrant = migrant.clone();
+                    migrant.set_network_config(network_config.clone());
+                    island.species_mut()[s].networks_mut()[n] = migrant;
+                }
+            }
+        }
+    }
+
+    /// The fittest network of every island
+    pub fn best_network(&self) -> Option<&NeatNetwork> {
+        self.islands.iter()
+            .filter_map(|island| ranked(island).last().map(|&(s, n)| &island.species()[s].networks()[n]))
+            .max_by(|a, b| a.previous_average_fitness().total_cmp(&b.previous_average_fitness()))
+    }
+
+    // Getters
+    pub fn islands(&self) -> &Vec<Evolution<F>> { &self.islands }
+    pub fn config(&self) -> &MigrationConfig { &self.config }
+    pub fn get_generation(&self) -> usize { self.generation }
+}
+
+/// (inputs, outputs) of the networks on `island`, None
+/// if it has no networks
+fn sizes<F: FitnessEvaluator + Send + Sync>(island: &Evolution<F>) -> Option<(usize, usize)> {
+    island.species().iter().flat_map(|species| species.networks().iter()).next()
+        .map(|network| (network.input_size(), network.output_size()))
+}
+
+/// (species index, network index) of every network on
+/// `island`, from the worst to the best average fitness
+fn ranked<F: FitnessEvaluator + Send + Sync>(island: &Evolution<F>) -> Vec<(usize, usize)> {
+    let mut networks: Vec<(usize, usize, f32)> = island.species().iter().enumerate()
+        .flat_map(|(s, species)| species.networks().iter().enumerate().map(move |(n, network)| (s, n, network.previous_average_fitness())))
+        .collect();
+    networks.sort_by(|a, b| a.2.total_cmp(&b.2));
+    networks.into_iter().map(|(s, n, _)| (s, n)).collect()
+}
@@ -0,0 +1,177 @@
+/* Imports */
+use rand::thread_rng;
+use rand_distr::{Distribution, StandardNormal};
+use crate::neural_network::network::NeatNetwork;
+use super::{config::cma_es::CmaEsConfig, fitness::FitnessEvaluator};
+
+/// Separable CMA-ES (Ros & Hansen), which only adapts the diagonal
+/// of the covariance matrix. That makes every generation linear in
+/// the amount of parameters, which is what NEAT networks need as
+/// they keep growing. Fitness is maximized.
+///
+/// ```ignore
+/// let mut cma = CmaEs::new(network.parameter_vector(), 0.3, None);
+/// for _ in 0..100 {
+///     let samples = cma.ask();
+///     let fitnesses = samples.iter().map(|s| evaluate(s)).collect();
+///     cma.tell(&samples, &fitnesses);
+/// }
+/// ```
+pub struct CmaEs {
+    mean: Vec<f32>,
+    step_size: f32,
+
+    /// Diagonal of the covariance matrix
+    variances: Vec<f32>,
+    evolution_path: Vec<f32>,
+    step_size_path: Vec<f32>,
+    generation: usize,
+
+    population_size: usize,
+    /// Recombination weights of the best `weights.len()` samples
+    weights: Vec<f32>,
+    mu_effective: f32,
+    step_size_learning_rate: f32,
+    step_size_damping: f32,
+    path_learning_rate: f32,
+    rank_one_learning_rate: f32,
+    rank_mu_learning_rate: f32,
+
+    /// Expected length of a N(0, I) vector
+    expected_norm: f32,
+}
+
+impl CmaEs {
+    pub fn new(mean: Vec<f32>, step_size: f32, population_size: Option<usize>) -> Self {
+        let n = mean.len().max(1) as f32;
+        let population_size = population_size.unwrap_or(4 + (3.0 * n.ln()).floor() as usize).max(2);
+        let mu = population_size / 2;
+
+        let mut weights: Vec<f32> = (1..=mu).map(|i| (mu as f32 + 0.5).ln() - (i as f32).ln()).collect();
+        let sum: f32 = weights.iter().sum();
+        weights.iter_mut().for_each(|w| *w /= sum);
+        let mu_effective = 1.0 / weights.iter().map(|w| w * w).sum::<f32>();
+
+        let step_size_learning_rate = (mu_effective + 2.0) / (n + mu_effective + 5.0);
+        let step_size_damping = 1.0 + 2.0 * (((mu_effective - 1.0) / (n + 1.0)).sqrt() - 1.0).max(0.0) + step_size_learning_rate;
+        let path_learning_rate = (4.0 + mu_effective / n) / (n + 4.0 + 2.0 * mu_effective / n);
+
+        // The separable variant may learn (n + 2) / 3 times faster
+        let rank_one = 2.0 / ((n + 1.3).powi(2) + mu_effective);
+        let rank_mu = 2.0 * (mu_effective - 2.0 + 1.0 / mu_effective) / ((n + 2.0).powi(2) + mu_effective);
+        let rank_one_learning_rate = (rank_one * (n + 2.0) / 3.0).min(1.0);
+        let rank_mu_learning_rate = (rank_mu * (n + 2.0) / 3.0).min(1.0 - rank_one_learning_rate);
+
+        Self {
+            variances: vec![1.0; mean.len()],
+            evolution_path: vec![0.0; mean.len()],
+            step_size_path: vec![0.0; mean.len()],
+            mean,
+            step_size,
+            generation: 0,
+            population_size,
+            weights,
+            mu_effective,
+            step_size_learning_rate,
+            step_size_damping,
+            path_learning_rate,
+            rank_one_learning_rate,
+            rank_mu_learning_rate,
+            expected_norm: n.sqrt() * (1.0 - 1.0 / (4.0 * n) + 1.0 / (21.0 * n * n)),
+        }
+    }
+
+    /// Samples `population_size` new parameter vectors
+    pub fn ask(&self) -> Vec<Vec<f32>> {
+        let mut rng = thread_rng();
+        (0..self.population_size).map(|_| {
+            self.mean.iter().zip(&self.variances).map(|(m, v)| {
+                let z: f32 = StandardNormal.sample(&mut rng);
+                m + self.step_size * v.sqrt() * z
+            }).collect()
+        }).collect()
+    }
+
+    /// Moves the distribution towards the fittest samples
+    pub fn tell(&mut self, samples: &[Vec<f32>], fitnesses: &[f32]) {
+        assert!(samples.len() == fitnesses.len() && samples.len() >= self.weights.len());
+        let n = self.mean.len();
+        self.generation += 1;
+
+        let mut order: Vec<usize> = (0..samples.len()).collect();
+        order.sort_by(|&a, &b| fitnesses[b].total_cmp(&fitnesses[a]));
+        let elites: Vec<&Vec<f32>> = order.iter().take(self.weights.len()).map(|&i| &samples[i]).collect();
+
+        let old_mean = self.mean.clone();
+        for i in 0..n {
+            self.mean[i] = elites.iter().zip(&self.weights).map(|(x, w)| w * x[i]).sum();
+        }
+        let mean_step: Vec<f32> = (0..n).map(|i| (self.mean[i] - old_mean[i]) / self.step_size).collect();
+
+        /* Step size path */
+        let c_sigma = self.step_size_learning_rate;
+        let sigma_scale = (c_sigma * (2.0 - c_sigma) * self.mu_effective).sqrt();
+        for ((path, step), variance) in self.step_size_path.iter_mut().zip(&mean_step).zip(&self.variances) {
+            *path = (1.0 - c_sigma) * *path + sigma_scale * step / variance.sqrt();
+        }
+        let path_norm = self.step_size_path.iter().map(|p| p * p).sum::<f32>().sqrt();
+
+        // Stall the covariance path while the step size grows quickly
+        let correction = (1.0 - (1.0 - c_sigma).powi(2 * self.generation as i32)).sqrt();
+        let stalled = path_norm / correction >= (1.4 + 2.0 / (n as f32 + 1.0)) * self.expected_norm;
+
+        /* Covariance path and diagonal */
+        let c_c = self.path_learning_rate;
+        let path_scale = if stalled { 0.0 } else { (c_c * (2.0 - c_c) * self.mu_effective).sqrt() };
+        let (c_1, c_mu) = (self.rank_one_learning_rate, self.rank_mu_learning_rate);
+        for i in 0..n {
+            self.evolution_path[i] = (1.0 - c_c) * self.evolution_path[i] + path_scale * mean_step[i];
+
+            let rank_one = self.evolution_path[i].powi(2) + if stalled { c_c * (2.0 - c_c) * self.variances[i] } else { 0.0 };
+            let rank_mu: f32 = elites.iter().zip(&self.weights)
+                .map(|(x, w)| w * ((x[i] - old_mean[i]) / self.step_size).powi(2))
+                .sum();
+            self.variances[i] = (1.0 - c_1 - c_mu) * self.variances[i] + c_1 * rank_one + c_mu * rank_mu;
+        }
+
+        self.step_size *= ((c_sigma / self.step_size_damping) * (path_norm / self.expected_norm - 1.0)).exp();
+    }
+
+    // Getters
+    pub fn mean(&self) -> &Vec<f32> { &self.mean }
+    pub fn step_size(&self) -> f32 { self.step_size }
+    pub fn population_size(&self) -> usize { self.population_size }
+}
+
+/// Optimizes the enabled weights and biases of `network` (see
+/// `NeatNetwork::parameter_vector`) against `evaluator`, keeping the
+/// topology as is. The network is only changed if a fitter set of
+/// weights was found. Returns the fitness of the network afterwards.
+pub fn polish<F: FitnessEvaluator>(network: &mut NeatNetwork, evaluator: &mut F, config: &CmaEsConfig) -> f32 {
+    network.reset_plasticity();
+    let mut best_fitness = evaluator.run(network);
+    let mut best = network.parameter_vector();
+    if best.is_empty() { return best_fitness }
+
+    let mut cma = CmaEs::new(best.clone(), config.initial_step_size, config.population_size);
+    let mut candidate = network.clone();
+    for _ in 0..config.generations {
+        let samples = cma.ask();
+        let fitnesses: Vec<f32> = samples.iter().map(|sample| {
+            candidate.set_parameter_vector(sample);
+            candidate.reset_plasticity();
+            evaluator.run(&mut candidate)
+        }).collect();
+
+        for (sample, &fitness) in samples.iter().zip(&fitnesses) {
+            if fitness > best_fitness {
+                best_fitness = fitness;
+                best = sample.clone();
+            }
+        }
+        cma.tell(&samples, &fitnesses);
+    }
+
+    network.set_parameter_vector(&best);
+    best_fitness
+}
@@ -0,0 +1,132 @@
+/* Imports */
+use rand::{seq::SliceRandom, thread_rng};
+use crate::neural_network::network::NeatNetwork;
+use super::{config::competitive::{Aggregation, CompetitiveConfig, Matchmaking}, fitness::FitnessEvaluator};
+
+/// A game between two networks, e.g `games::tic_tac_toe`
+pub trait PairwiseEvaluator: Clone {
+    /// Plays `a` against `b` and returns the score of each
+    /// side, (a, b). Scores need to be >= 0.0.
+    fn run(&mut self, a: &mut NeatNetwork, b: &mut NeatNetwork) -> (f32, f32);
+}
+
+/// Lets `Evolution` evolve networks by playing them against
+/// eachother (self-play) instead of against an environment.
+/// Every generation the opponents are picked from the population
+/// (see `Matchmaking`) and every network plays all of them, the
+/// scores are then aggregated into its fitness.
+#[derive(Clone)]
+pub struct CompetitiveEvaluator<P: PairwiseEvaluator> {
+    evaluator: P,
+    config: CompetitiveConfig,
+
+    /// Who every network plays this generation
+    opponents: Vec<NeatNetwork>,
+
+    /// Best network of every previous generation, newest last
+    hall_of_fame: Vec<NeatNetwork>,
+    generation: usize,
+}
+
+impl<P: PairwiseEvaluator> CompetitiveEvaluator<P> {
+    pub fn new(evaluator: P, config: CompetitiveConfig) -> Self {
+        Self { evaluator, config, opponents: Vec::new(), hall_of_fame: Vec::new(), generation: 0 }
+    }
+
+    /// Plays `a` against `b` with fresh plastic weights
+    pub fn play(&mut self, a: &mut NeatNetwork, b: &mut NeatNetwork) -> (f32, f32) {
+        a.reset_plasticity();
+        b.reset_plasticity();
+        self.evaluator.run(a, b)
+    }
+
+    /// `amount` opponents which together beat as many different
+    /// candidates as possible. Beating a candidate few others
+    /// beat counts more.
+    fn shared_sample(&mut self, mut candidates: Vec<NeatNetwork>, amount: usize) -> Vec<NeatNetwork> {
+        let size = candidates.len();
+        let mut beats = vec![vec![false; size]; size];
+        for i in 0..size {
+            for j in i + 1..size {
+                let (mut a, mut b) = (candidates[i].clone(), candidates[j].clone());
+                let (score_a, score_b) = self.play(&mut a, &mut b);
+                beats[i][j] = score_a > score_b;
+                beats[j][i] = score_b > score_a;
+            }
+        }
+
+        let mut beaten_by_picked = vec![0; size];
+        let mut picked: Vec<usize> = Vec::with_capacity(amount);
+        while picked.len() < amount.min(size) {
+            let value = |candidate: usize| -> f32 {
+                (0..size).filter(|&j| beats[candidate][j]).map(|j| 1.0 / (1.0 + beaten_by_picked[j] as f32)).sum()
+            };
+            let best = (0..size)
+                .filter(|candidate| !picked.contains(candidate))
+                .max_by(|&a, &b| value(a).total_cmp(&value(b)))
+                .unwrap();
+
+            for (j, beaten) in beaten_by_picked.iter_mut().enumerate() {
+                if beats[best][j] { *beaten += 1; }
+            }
+            picked.push(best);
+        }
+
+        // Take them out back to front so indexes stay valid
+        picked.sort_unstable_by(|a, b| b.cmp(a));
+        picked.into_iter().map(|index| candidates.swap_remove(index)).collect()
+    }
+
+    // Getters
+    pub fn opponents(&self) -> &Vec<NeatNetwork> { &self.opponents }
+    pub fn hall_of_fame(&self) -> &Vec<NeatNetwork> { &self.hall_of_fame }
+    pub fn config(&self) -> &CompetitiveConfig { &self.config }
+    pub fn evaluator(&self) -> &P { &self.evaluator }
+}
+
+impl<P: PairwiseEvaluator> FitnessEvaluator for CompetitiveEvaluator<P> {
+    fn run(&mut self, network: &mut NeatNetwork) -> f32 {
+        let mut opponents = std::mem::take(&mut self.opponents);
+        let results: Vec<(f32, f32)> = opponents.iter_mut().map(|opponent| self.play(network, opponent)).collect();
+        self.opponents = opponents;
+        if results.is_empty() { return 0.0 }
+
+        match self.config.aggregation {
+            Aggregation::Mean => results.iter().map(|(score, _)| score).sum::<f32>() / results.len() as f32,
+            Aggregation::Min => results.iter().map(|(score, _)| *score).fold(f32::MAX, f32::min),
+            Aggregation::WinRate => results.iter().filter(|(score, other)| score > other).count() as f32 / results.len() as f32,
+        }
+    }
+
+    fn prepare_generation(&mut self, population: &[&NeatNetwork]) {
+        // The population holds the fitnesses of the previous
+        // generation, so its best network is that generation's champion
+        if self.generation > 0 {
+            let champion = population.iter().max_by(|a, b| a.previous_fitness().total_cmp(&b.previous_fitness()));
+            if let Some(champion) = champion {
+                self.hall_of_fame.push((*champion).clone());
+                if self.hall_of_fame.len() > self.config.hall_of_fame_size {
+                    self.hall_of_fame.remove(0);
+                }
+            }
+        }
+        self.generation += 1;
+
+        let sample = |amount: usize| -> Vec<NeatNetwork> {
+            population.choose_multiple(&mut thread_rng(), amount).map(|network| (*network).clone()).collect()
+        };
+        self.opponents = match self.config.matchmaking {
+            Matchmaking::RoundRobin(amount) => sample(amount),
+
+            // Nobody to look up to in the first generation
+            Matchmaking::HallOfFame(amount) if self.hall_of_fame.is_empty() => sample(amount),
+            Matchmaking::HallOfFame(amount) => {
+                self.hall_of_fame.iter().rev().take(amount).cloned().collect()
+            },
+            Matchmaking::SharedSampling(amount) => {
+                let candidates = sample(amount * 2);
+                self.shared_sample(candidates, amount)
+            },
+        };
+    }
+}
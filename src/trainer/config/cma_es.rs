@@ -0,0 +1,29 @@
+/// Settings for polishing the weights of a network with
+/// CMA-ES, see `trainer::cma_es`
+#[derive(Clone, Copy)]
+pub struct CmaEsConfig {
+    /// Standard deviation of the first samples
+    pub initial_step_size: f32,
+
+    /// Generations (ask / tell rounds) per polish
+    pub generations: usize,
+
+    /// Samples per generation. None = 4 + 3 ln(n), the usual
+    /// default for n parameters
+    pub population_size: Option<usize>,
+
+    /// How often `Evolution` polishes the best network of
+    /// every species
+    pub every_nth_gen: usize,
+}
+
+impl Default for CmaEsConfig {
+    fn default() -> Self {
+        Self {
+            initial_step_size: 0.3,
+            generations: 20,
+            population_size: None,
+            every_nth_gen: 25,
+        }
+    }
+}
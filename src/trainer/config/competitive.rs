@@ -0,0 +1,49 @@
+/// Who every network plays against, see `CompetitiveEvaluator`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Matchmaking {
+    /// Every network plays every network of a random
+    /// sample of this size from the population
+    RoundRobin(usize),
+
+    /// Every network plays the latest n champions, the
+    /// best network of every previous generation
+    HallOfFame(usize),
+
+    /// Every network plays n opponents from the population
+    /// which together beat as many different networks as
+    /// possible (shared sampling, Rosin & Belew). Picked
+    /// from a round robin between 2n candidates.
+    SharedSampling(usize),
+}
+
+/// How the scores of all matches of a network become its fitness
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Aggregation {
+    Mean,
+
+    /// The worst match, rewards networks without weaknesses
+    Min,
+
+    /// Fraction of matches which scored higher than the opponent
+    WinRate,
+}
+
+/// Competitive co-evolution, see `CompetitiveEvaluator`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CompetitiveConfig {
+    pub matchmaking: Matchmaking,
+    pub aggregation: Aggregation,
+
+    /// How many champions the hall of fame remembers
+    pub hall_of_fame_size: usize,
+}
+
+impl Default for CompetitiveConfig {
+    fn default() -> Self {
+        Self {
+            matchmaking: Matchmaking::RoundRobin(8),
+            aggregation: Aggregation::Mean,
+            hall_of_fame_size: 50,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+/// How the fitness of a network is derived from the teams
+/// it was evaluated in, see `CooperativeEvolution`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CreditAssignment {
+    /// The best team, optimistic, rewards networks
+    /// which work well with at least one partner
+    BestPartner,
+
+    /// The average over all teams, rewards networks
+    /// which work well with any partner
+    AverageOverPartners,
+}
+
+/// Cooperative co-evolution, see `CooperativeEvolution`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CooperativeConfig {
+    pub credit_assignment: CreditAssignment,
+
+    /// How many teams every network is evaluated in. The first
+    /// team consists of the best network of every other
+    /// population, the rest of random ones.
+    pub partners: usize,
+}
+
+impl Default for CooperativeConfig {
+    fn default() -> Self {
+        Self {
+            credit_assignment: CreditAssignment::BestPartner,
+            partners: 3,
+        }
+    }
+}
@@ -0,0 +1,39 @@
+/* Imports */
+use crate::neural_network::ctrnn::Integration;
+
+/// Evolves the time constants of nodes, for networks which
+/// are run with `NeatNetwork::step`
+#[derive(Clone, Copy)]
+pub struct CtrnnConfig {
+    /// Weighted like the probabilities in `GenomeMutationProbablities`,
+    /// how likely a mutation is to change the time constant of a
+    /// random node
+    pub mutation_probability: usize,
+
+    /// The most a time constant can change in one mutation
+    pub time_constant_step: f32,
+
+    /// Time constants never go below this. Should not be smaller
+    /// than the dt the network is stepped with.
+    pub min_time_constant: f32,
+
+    pub integration: Integration,
+
+    /// Lets mutations, crossover, `NeatNetwork::add_connection` and
+    /// `NetworkBuilder` create connections which form cycles, like
+    /// self loops or connections back to earlier hidden nodes. Such
+    /// networks only make sense to run with `NeatNetwork::step`.
+    pub recurrent: bool,
+}
+
+impl Default for CtrnnConfig {
+    fn default() -> Self {
+        Self {
+            mutation_probability: 40,
+            time_constant_step: 0.2,
+            min_time_constant: 0.05,
+            integration: Integration::Euler,
+            recurrent: false,
+        }
+    }
+}
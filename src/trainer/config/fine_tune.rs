@@ -0,0 +1,24 @@
+/* Imports */
+use std::sync::Arc;
+use crate::neural_network::gradient::{Loss, Optimizer};
+
+/// Lamarckian fine tuning: every nth generation each network is
+/// trained with `NeatNetwork::fine_tune` and keeps the weights
+/// it learned.
+#[derive(Clone)]
+pub struct FineTuneConfig {
+    /// (input, target) pairs
+    pub dataset: Arc<Vec<(Vec<f32>, Vec<f32>)>>,
+    pub loss: Loss,
+    pub optimizer: Optimizer,
+
+    /// Epochs per fine tuning
+    pub epochs: usize,
+    pub every_nth_gen: usize,
+}
+
+impl FineTuneConfig {
+    pub fn new(dataset: Vec<(Vec<f32>, Vec<f32>)>, loss: Loss, optimizer: Optimizer) -> Self {
+        Self { dataset: Arc::new(dataset), loss, optimizer, epochs: 5, every_nth_gen: 10 }
+    }
+}
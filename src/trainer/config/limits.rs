@@ -0,0 +1,14 @@
+/// Hard caps on the size of a genome. Structural mutations
+/// which would break one of them don't happen. None = no limit
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct ComplexityLimits {
+    pub max_hidden_nodes: Option<usize>,
+
+    /// Counts disabled connection genes too
+    pub max_connections: Option<usize>,
+
+    /// Most connections on any path through the network, see
+    /// `NeatNetwork::depth`. Ignored when `CtrnnConfig::recurrent`
+    /// is set, as paths through cycles have no length
+    pub max_depth: Option<usize>,
+}
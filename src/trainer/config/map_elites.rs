@@ -0,0 +1,61 @@
+/* Imports */
+use crate::neural_network::activation::NetworkActivations;
+use super::network_config::NetworkConfig;
+
+/// One axis of the MAP-Elites grid. Behaviour values in
+/// [min, max] are split into `bins` equally wide cells,
+/// values outside of it land in the outermost cells.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BehaviourDimension {
+    pub min: f32,
+    pub max: f32,
+    pub bins: usize,
+}
+
+/// MAP-Elites, see `MapElites`
+#[derive(Clone)]
+pub struct MapElitesConfig {
+    /// One dimension per value of `Evaluation::behaviour`
+    pub dimensions: Vec<BehaviourDimension>,
+
+    /// How many children are created and evaluated every generation
+    pub batch_size: usize,
+
+    /// Chance (0.0 - 1.0) that a child is the crossover of two
+    /// random elites instead of a clone of one, before mutating
+    pub crossover_probability: f64,
+
+    pub activations: NetworkActivations,
+    pub network_config: NetworkConfig,
+}
+
+impl BehaviourDimension {
+    pub fn new(min: f32, max: f32, bins: usize) -> Self {
+        assert!(max > min && bins > 0, "Behaviour dimensions need a range and at least one bin");
+        Self { min, max, bins }
+    }
+
+    /// The bin `value` falls into
+    pub fn bin(&self, value: f32) -> usize {
+        let scaled = (value - self.min) / (self.max - self.min) * self.bins as f32;
+        (scaled.max(0.0) as usize).min(self.bins - 1)
+    }
+}
+
+impl MapElitesConfig {
+    pub fn new(dimensions: Vec<BehaviourDimension>) -> Self {
+        Self { dimensions, ..Default::default() }
+    }
+}
+
+impl Default for MapElitesConfig {
+    fn default() -> Self {
+        Self {
+            dimensions: Vec::new(),
+            batch_size: 100,
+            crossover_probability: 0.0,
+            activations: NetworkActivations::default(),
+            network_config: NetworkConfig::default(),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+/// Which islands send migrants to which, see `Archipelago`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MigrationTopology {
+    /// Island i sends to island i + 1, the last to the first
+    Ring,
+
+    /// Every island sends to every other island
+    FullyConnected,
+
+    /// Every island sends to one random other island
+    Random,
+}
+
+/// Island model, see `Archipelago`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MigrationConfig {
+    pub topology: MigrationTopology,
+
+    /// Migrate every nth generation
+    pub interval: usize,
+
+    /// How many of its best networks an island sends to each
+    /// destination, replacing the worst networks there
+    pub migrants: usize,
+}
+
+impl Default for MigrationConfig {
+    fn default() -> Self {
+        Self {
+            topology: MigrationTopology::Ring,
+            interval: 10,
+            migrants: 2,
+        }
+    }
+}
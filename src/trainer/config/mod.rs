@@ -1,3 +1,18 @@
 pub mod stop_condition;
 pub mod mutation;
 pub mod network_config;
+pub mod plasticity;
+pub mod ctrnn;
+pub mod fine_tune;
+pub mod cma_es;
+pub mod novelty;
+pub mod map_elites;
+pub mod multi_objective;
+pub mod competitive;
+pub mod cooperative;
+pub mod migration;
+pub mod real_time;
+pub mod phased_search;
+pub mod limits;
+pub mod parsimony;
+pub mod self_adaptation;
@@ -0,0 +1,48 @@
+/* Imports */
+use crate::neural_network::network::NeatNetwork;
+
+/// Network size, as an objective to minimize
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Complexity {
+    EnabledConnections,
+
+    /// Hidden nodes with at least one connection, nodes which were
+    /// disconnected by a `delete_node` mutation don't count
+    HiddenNodes,
+
+    /// Enabled connections + hidden nodes
+    Both,
+}
+
+/// NSGA-II selection over a fitness vector, see `nsga2`
+#[derive(Clone, Copy, Default)]
+pub struct MultiObjectiveConfig {
+    /// Adds an objective which minimizes the size of the network,
+    /// next to the objectives of the evaluator. None = off
+    pub minimize_complexity: Option<Complexity>,
+}
+
+impl Complexity {
+    pub fn of(&self, network: &NeatNetwork) -> usize {
+        let connections = network.get_genes().iter().filter(|c| c.enabled()).count();
+        let hidden = network.connected_hidden_nodes();
+        match self {
+            Self::EnabledConnections => connections,
+            Self::HiddenNodes => hidden,
+            Self::Both => connections + hidden,
+        }
+    }
+}
+
+impl MultiObjectiveConfig {
+    /// Every objective `network` is ranked on, all maximized: the
+    /// objectives of its latest evaluation (or its average fitness if
+    /// there were none), then the negated complexity if enabled
+    pub fn objectives(&self, network: &NeatNetwork) -> Vec<f32> {
+        let mut objectives = if network.objectives().is_empty() { vec![network.previous_average_fitness()] } else { network.objectives().clone() };
+        if let Some(complexity) = self.minimize_complexity {
+            objectives.push(-(complexity.of(network) as f32));
+        }
+        objectives
+    }
+}
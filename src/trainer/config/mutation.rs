@@ -5,6 +5,12 @@ pub struct GenomeMutationProbablities {
     pub create_connection: usize,
     pub change_weight: usize,
     pub toggle_weight: usize,
+
+    /// Remove a random connection gene
+    pub delete_connection: usize,
+
+    /// Remove every connection of a random hidden node
+    pub delete_node: usize,
     pub nothing: usize,
 }
 
@@ -15,6 +21,8 @@ impl Default for GenomeMutationProbablities {
             create_connection: 8,
             change_weight: 350,
             toggle_weight: 2,
+            delete_connection: 0,
+            delete_node: 0,
             nothing: 20
         }
     }
@@ -40,3 +48,77 @@ impl Default for WeightChangeProbablities {
         }
     }
 }
+
+/// Lets every mutation fire on its own in the same mutation
+/// event, like most NEAT implementations do, instead of picking
+/// one from `GenomeMutationProbablities`. Each field is the
+/// chance of that mutation happening
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct IndependentMutations {
+    pub split_connection: f64,
+    pub create_connection: f64,
+    pub toggle_weight: f64,
+    pub delete_connection: f64,
+    pub delete_node: f64,
+
+    /// Only if plasticity / ctrnn is enabled
+    pub plasticity: f64,
+    pub time_constant: f64,
+
+    /// Mean of the poisson distributed amount of weight
+    /// mutations, 0.0 = weights don't change. With
+    /// `NetworkConfig::gaussian_weights` one pass over every
+    /// connection happens if the amount is above 0
+    pub weight_mutations: f32,
+}
+
+impl Default for IndependentMutations {
+    fn default() -> Self {
+        Self {
+            split_connection: 0.03,
+            create_connection: 0.05,
+            toggle_weight: 0.01,
+            delete_connection: 0.0,
+            delete_node: 0.0,
+            plasticity: 0.05,
+            time_constant: 0.05,
+            weight_mutations: 1.0,
+        }
+    }
+}
+
+/// Standard NEAT weight mutation. Every connection is perturbed
+/// by N(0, `sigma`) with `perturb_probability`, or gets a new
+/// weight from N(0, `replace_sigma`) with `replace_probability`,
+/// and is left alone otherwise
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GaussianWeights {
+    pub perturb_probability: f32,
+    pub sigma: f32,
+    pub replace_probability: f32,
+    pub replace_sigma: f32,
+}
+
+impl Default for GaussianWeights {
+    fn default() -> Self {
+        Self {
+            perturb_probability: 0.9,
+            sigma: 0.5,
+            replace_probability: 0.1,
+            replace_sigma: 1.0,
+        }
+    }
+}
+
+/// Weights are clamped to [min, max] after every mutation
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct WeightBounds {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Default for WeightBounds {
+    fn default() -> Self {
+        Self { min: -8.0, max: 8.0 }
+    }
+}
@@ -1,22 +1,75 @@
-use super::mutation::{GenomeMutationProbablities, WeightChangeProbablities};
+use crate::neural_network::mutation_operator::WeightedOperator;
+use super::{mutation::{GaussianWeights, GenomeMutationProbablities, IndependentMutations, WeightBounds, WeightChangeProbablities}, plasticity::PlasticityConfig, ctrnn::CtrnnConfig, limits::ComplexityLimits, self_adaptation::SelfAdaptationConfig};
 
 #[derive(Clone)]
 pub struct NetworkConfig {
     pub mutation_probabilities: GenomeMutationProbablities,
+
+    /// Mutations fire independently, several per `mutate` call.
+    /// None = one mutation picked from `mutation_probabilities`
+    pub independent_mutations: Option<IndependentMutations>,
+
+    /// Custom mutations next to the built in ones. The weight is
+    /// relative to `mutation_probabilities` when one mutation is
+    /// picked, and the chance of firing with independent mutations
+    pub mutation_operators: Vec<WeightedOperator>,
     pub weight_change_probabilities: WeightChangeProbablities,
 
+    /// Perturb every connection's weight when weights are mutated
+    /// instead of one with `weight_change_probabilities`. None =
+    /// one connection at a time
+    pub gaussian_weights: Option<GaussianWeights>,
+
+    /// None = weights are unbounded
+    pub weight_bounds: Option<WeightBounds>,
+
     /// If we should initialize networks with pre-
     /// established connections between input and
     /// output neurons. (Bias nodes not included)
     pub initialize_with_connections: bool,
+
+    /// Run `NeatNetwork::validate` after every mutation and
+    /// panic if any invariant was broken. Only has an effect
+    /// in debug builds.
+    pub validate_after_mutation: bool,
+
+    /// Evolve hebbian learning rules for connections. None = all
+    /// connections keep their weight during an episode
+    pub plasticity: Option<PlasticityConfig>,
+
+    /// Evolve node time constants and set how `NeatNetwork::step`
+    /// integrates. None = time constants stay at 1.0
+    pub ctrnn: Option<CtrnnConfig>,
+
+    /// Caps on hidden nodes, connections and depth which
+    /// structural mutations respect
+    pub limits: ComplexityLimits,
+
+    /// Let mutation rates and step sizes adapt, see
+    /// `MutationRates`. None = the rates above are used as is
+    pub self_adaptation: Option<SelfAdaptationConfig>,
+}
+
+impl NetworkConfig {
+    /// If connections may form cycles, see `CtrnnConfig::recurrent`
+    pub fn recurrent(&self) -> bool { self.ctrnn.is_some_and(|ctrnn| ctrnn.recurrent) }
 }
 
 impl Default for NetworkConfig {
     fn default() -> Self {
         Self {
             mutation_probabilities: Default::default(),
+            independent_mutations: None,
+            mutation_operators: Vec::new(),
             weight_change_probabilities: Default::default(),
-            initialize_with_connections: true
+            gaussian_weights: None,
+            weight_bounds: None,
+            initialize_with_connections: true,
+            validate_after_mutation: false,
+            plasticity: None,
+            ctrnn: None,
+            limits: ComplexityLimits::default(),
+            self_adaptation: None,
         }
     }
 }
@@ -0,0 +1,36 @@
+/// When a behaviour is remembered in the novelty archive
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ArchiveInsertion {
+    /// Every behaviour at least this novel
+    Threshold(f32),
+
+    /// Every behaviour with this probability
+    Random(f64),
+
+    /// The n most novel behaviours of each generation
+    MostNovel(usize),
+}
+
+/// Novelty search, see `NoveltyArchive`
+#[derive(Clone, Copy)]
+pub struct NoveltyConfig {
+    /// How many nearest neighbours novelty is measured against
+    pub k: usize,
+    pub insertion: ArchiveInsertion,
+
+    /// Networks are selected by `(1 - novelty_weight) * fitness +
+    /// novelty_weight * novelty`, 1.0 = pure novelty search. Fitness
+    /// and novelty aren't normalized, so the weight depends on
+    /// their scales.
+    pub novelty_weight: f32,
+}
+
+impl Default for NoveltyConfig {
+    fn default() -> Self {
+        Self {
+            k: 15,
+            insertion: ArchiveInsertion::MostNovel(2),
+            novelty_weight: 1.0,
+        }
+    }
+}
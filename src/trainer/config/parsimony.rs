@@ -0,0 +1,26 @@
+/* Imports */
+use crate::neural_network::network::NeatNetwork;
+use super::multi_objective::Complexity;
+
+/// Parsimony pressure, networks are selected by their average
+/// fitness minus `coefficient * complexity`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ParsimonyConfig {
+    pub complexity: Complexity,
+    pub coefficient: f32,
+}
+
+impl ParsimonyConfig {
+    pub fn penalty(&self, network: &NeatNetwork) -> f32 {
+        self.coefficient * self.complexity.of(network) as f32
+    }
+}
+
+impl Default for ParsimonyConfig {
+    fn default() -> Self {
+        Self {
+            complexity: Complexity::Both,
+            coefficient: 0.01,
+        }
+    }
+}
@@ -0,0 +1,46 @@
+/* Imports */
+use super::{multi_objective::Complexity, mutation::GenomeMutationProbablities};
+
+/// Phased search, see `PhasedSearch`
+#[derive(Clone, Copy)]
+pub struct PhasedSearchConfig {
+    /// How the size of a network is measured
+    pub complexity: Complexity,
+
+    /// Simplify when the mean complexity grows this much above
+    /// the mean complexity at the end of the last simplification
+    /// (or at the start)
+    pub complexity_jump: f32,
+
+    /// Also simplify when the best fitness hasn't improved for
+    /// this many generations. 0 = only by complexity
+    pub plateau_generations: usize,
+
+    /// Go back to complexifying when the mean complexity hasn't
+    /// dropped for this many generations
+    pub stall_generations: usize,
+
+    /// Mutations while simplifying, which should only be deletions.
+    /// Always picked one at a time, even with independent mutations
+    pub simplification_mutations: GenomeMutationProbablities,
+}
+
+impl Default for PhasedSearchConfig {
+    fn default() -> Self {
+        Self {
+            complexity: Complexity::Both,
+            complexity_jump: 30.0,
+            plateau_generations: 0,
+            stall_generations: 10,
+            simplification_mutations: GenomeMutationProbablities {
+                split_connection: 0,
+                create_connection: 0,
+                change_weight: 0,
+                toggle_weight: 0,
+                delete_connection: 10,
+                delete_node: 5,
+                nothing: 5,
+            },
+        }
+    }
+}
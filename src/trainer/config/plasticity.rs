@@ -0,0 +1,23 @@
+/// Makes connections plastic, see `HebbianRule`. Connections
+/// start out static, and get a learning rule the first time
+/// it's mutated.
+#[derive(Clone, Copy)]
+pub struct PlasticityConfig {
+    /// Weighted like the probabilities in `GenomeMutationProbablities`,
+    /// how likely a mutation is to change the learning rule of a
+    /// random connection
+    pub mutation_probability: usize,
+
+    /// The most a single learning rule coefficient can
+    /// change in one mutation
+    pub coefficient_step: f32,
+}
+
+impl Default for PlasticityConfig {
+    fn default() -> Self {
+        Self {
+            mutation_probability: 40,
+            coefficient_step: 0.05,
+        }
+    }
+}
@@ -0,0 +1,24 @@
+/// Steady state evolution, see `RealTimeEvolution`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RealTimeConfig {
+    /// Replace a network every nth tick
+    pub replacement_interval: usize,
+
+    /// Networks younger than this (in ticks) are never
+    /// replaced, so they get time to prove themselves
+    pub minimum_age: usize,
+
+    /// Chance (0.0 - 1.0) that an offspring is the crossover of
+    /// two parents instead of a mutated copy of one
+    pub crossover_probability: f64,
+}
+
+impl Default for RealTimeConfig {
+    fn default() -> Self {
+        Self {
+            replacement_interval: 20,
+            minimum_age: 100,
+            crossover_probability: 0.3,
+        }
+    }
+}
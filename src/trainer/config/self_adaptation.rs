@@ -0,0 +1,89 @@
+/* Imports */
+use rand::thread_rng;
+use rand_distr::{Distribution, StandardNormal};
+
+/// How mutation rates adapt during evolution
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Adaptation {
+    /// Every genome carries its own rates, which are multiplied
+    /// by `exp(learning_rate * N(0, 1))` before each mutation
+    /// (evolution strategies style) and are inherited by its
+    /// clones and crossover offspring
+    LogNormal { learning_rate: f32 },
+
+    /// Rechenberg's 1/5th success rule over the whole population.
+    /// If more than a fifth of the mutations improved fitness all
+    /// rates are divided by `factor` (< 1.0), if fewer they're
+    /// multiplied by it
+    OneFifthRule { factor: f32 },
+}
+
+/// Self-adaptive mutation rates, which scale the probabilities in
+/// `GenomeMutationProbablities` and the weight step sizes
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SelfAdaptationConfig {
+    pub adaptation: Adaptation,
+
+    /// Bounds of every rate
+    pub min_rate: f32,
+    pub max_rate: f32,
+}
+
+/// Multipliers a genome applies to the mutation probabilities of
+/// its network config. `nothing` isn't scaled, so raising every
+/// rate makes mutations more frequent overall.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MutationRates {
+    pub split_connection: f32,
+    pub create_connection: f32,
+    pub change_weight: f32,
+    pub toggle_weight: f32,
+    pub delete_connection: f32,
+    pub delete_node: f32,
+
+    /// Multiplies how far weights move when changed
+    pub step_size: f32,
+}
+
+impl MutationRates {
+    /// Every rate set to `rate`
+    pub fn uniform(rate: f32) -> Self {
+        Self {
+            split_connection: rate,
+            create_connection: rate,
+            change_weight: rate,
+            toggle_weight: rate,
+            delete_connection: rate,
+            delete_node: rate,
+            step_size: rate,
+        }
+    }
+
+    /// Log-normal mutation of every rate, clamped to the bounds
+    pub fn mutate(&mut self, learning_rate: f32, min_rate: f32, max_rate: f32) {
+        let mut rng = thread_rng();
+        for rate in [
+            &mut self.split_connection, &mut self.create_connection,
+            &mut self.change_weight, &mut self.toggle_weight,
+            &mut self.delete_connection, &mut self.delete_node,
+            &mut self.step_size,
+        ] {
+            let noise: f32 = StandardNormal.sample(&mut rng);
+            *rate = (*rate * (learning_rate * noise).exp()).clamp(min_rate, max_rate);
+        }
+    }
+}
+
+impl Default for MutationRates {
+    fn default() -> Self { Self::uniform(1.0) }
+}
+
+impl Default for SelfAdaptationConfig {
+    fn default() -> Self {
+        Self {
+            adaptation: Adaptation::LogNormal { learning_rate: 0.2 },
+            min_rate: 0.01,
+            max_rate: 100.0,
+        }
+    }
+}
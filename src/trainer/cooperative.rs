@@ -0,0 +1,148 @@
+/* Imports */
+use rand::{seq::SliceRandom, thread_rng};
+use crate::neural_network::network::NeatNetwork;
+use super::{config::cooperative::{CooperativeConfig, CreditAssignment}, evolution::{Evolution, EvolutionBuilder}, fitness::FitnessEvaluator};
+
+/// Evaluates a team made of one network per population
+pub trait TeamEvaluator: Clone {
+    /// `team[i]` is the network from population `i`. Needs
+    /// to return a fitness >= 0.0 for the whole team.
+    fn run(&mut self, team: &mut [NeatNetwork]) -> f32;
+}
+
+/// The fitness evaluator of every population of a
+/// `CooperativeEvolution`. Teams the evaluated network up
+/// with the current partners from the other populations.
+#[derive(Clone)]
+pub struct TeamMember<T: TeamEvaluator> {
+    evaluator: T,
+    credit_assignment: CreditAssignment,
+
+    /// Index of the population this evaluates
+    population: usize,
+
+    /// Teams without this population's network, which is
+    /// inserted at `population` when evaluating
+    partner_teams: Vec<Vec<NeatNetwork>>,
+}
+
+/// Evolves several populations of networks at once, e.g a
+/// sensor preprocessing network and a controller, which are
+/// only evaluated together. Every population is a regular
+/// `Evolution` whose networks are evaluated in teams with
+/// partners from the other populations.
+///
+/// ```ignore
+/// let mut coop = CooperativeEvolution::new(evaluator, CooperativeConfig::default());
+/// coop.add_population(Evolution::new().batch_size(10).with_input_nodes(4).with_output_nodes(2))
+///     .add_population(Evolution::new().batch_size(10).with_input_nodes(2).with_output_nodes(1));
+/// for _ in 0..100 { coop.generation(); }
+/// ```
+pub struct CooperativeEvolution<T: TeamEvaluator + Send + Sync> {
+    evaluator: T,
+    config: CooperativeConfig,
+    populations: Vec<Evolution<TeamMember<T>>>,
+}
+
+impl<T: TeamEvaluator> TeamMember<T> {
+    pub fn new(evaluator: T, credit_assignment: CreditAssignment, population: usize) -> Self {
+        Self { evaluator, credit_assignment, population, partner_teams: Vec::new() }
+    }
+
+    pub fn set_partner_teams(&mut self, teams: Vec<Vec<NeatNetwork>>) { self.partner_teams = teams; }
+
+    // Getters
+    pub fn population(&self) -> usize { self.population }
+    pub fn partner_teams(&self) -> &Vec<Vec<NeatNetwork>> { &self.partner_teams }
+}
+
+impl<T: TeamEvaluator> FitnessEvaluator for TeamMember<T> {
+    fn run(&mut self, network: &mut NeatNetwork) -> f32 {
+        let mut teams = std::mem::take(&mut self.partner_teams);
+        let fitnesses: Vec<f32> = teams.iter_mut().map(|partners| {
+            let mut team = partners.clone();
+            team.insert(self.population, network.clone());
+            team.iter_mut().for_each(|member| member.reset_plasticity());
+            self.evaluator.run(&mut team)
+        }).collect();
+        self.partner_teams = teams;
+        if fitnesses.is_empty() { return 0.0 }
+
+        match self.credit_assignment {
+            CreditAssignment::BestPartner => fitnesses.iter().copied().fold(f32::MIN, f32::max),
+            CreditAssignment::AverageOverPartners => fitnesses.iter().sum::<f32>() / fitnesses.len() as f32,
+        }
+    }
+}
+
+impl<T: TeamEvaluator + Send + Sync> CooperativeEvolution<T> {
+    pub fn new(evaluator: T, config: CooperativeConfig) -> Self {
+        Self { evaluator, config, populations: Vec::new() }
+    }
+
+    /// Adds a population, configured like any other evolution.
+    /// Its fitness evaluator is set here. Panics if the population
+    /// has no networks, as every team needs a member from it.
+    pub fn add_population(&mut self, builder: &mut EvolutionBuilder<TeamMember<T>>) -> &mut Self {
+        let member = TeamMember::new(self.evaluator.clone(), self.config.credit_assignment, self.populations.len());
+        let population = builder.set_fitness_evaluator(member).build();
+        assert!(
+            population.species().iter().any(|species| !species.networks().is_empty()),
+            "Population {} has no networks, batch_size and species_size need to be above 0", self.populations.len()
+        );
+        self.populations.push(population);
+        self
+    }
+
+    /// Picks new partners from every population, then runs a
+    /// generation of each. Returns true if any population
+    /// wants to stop.
+    pub fn generation(&mut self) -> bool {
+        assert!(!self.populations.is_empty(), "Cooperative evolution needs at least one population");
+        let mut rng = thread_rng();
+
+        // Partner k of every population, the best first
+        let representatives: Vec<Vec<NeatNetwork>> = self.populations.iter().map(|population| {
+            let mut networks: Vec<&NeatNetwork> = population.species().iter().flat_map(|species| species.networks().iter()).collect();
+            networks.sort_by(|a, b| b.previous_average_fitness().total_cmp(&a.previous_average_fitness()));
+
+            // Populations are never empty, see `add_population`
+            let Some((best, others)) = networks.split_first() else { return Vec::new() };
+            let others = others.choose_multiple(&mut rng, self.config.partners.saturating_sub(1)).map(|network| (*network).clone());
+            std::iter::once((*best).clone()).chain(others).collect()
+        }).collect();
+
+        for (index, population) in self.populations.iter().enumerate() {
+            let teams: Vec<Vec<NeatNetwork>> = (0..self.config.partners.max(1)).map(|k| {
+                representatives.iter().enumerate()
+                    .filter(|(other, partners)| *other != index && !partners.is_empty())
+                    .map(|(_, partners)| partners[k % partners.len()].clone())
+                    .collect()
+            }).collect();
+            population.get_fitness_evaluator().lock().unwrap().set_partner_teams(teams);
+        }
+
+        let mut should_stop = false;
+        for population in self.populations.iter_mut() {
+            should_stop |= population.generation();
+        }
+        should_stop
+    }
+
+    /// The best network of every population as of the latest
+    /// generation, which together make up the best team
+    pub fn best_team(&self) -> Vec<NeatNetwork> {
+        self.populations.iter().map(|population| {
+            population.species().iter()
+                .flat_map(|species| species.networks().iter())
+                .max_by(|a, b| a.previous_average_fitness().total_cmp(&b.previous_average_fitness()))
+                .unwrap()
+                .clone()
+        }).collect()
+    }
+
+    // Getters
+    pub fn populations(&self) -> &Vec<Evolution<TeamMember<T>>> { &self.populations }
+    pub fn config(&self) -> &CooperativeConfig { &self.config }
+    pub fn evaluator(&self) -> &T { &self.evaluator }
+}
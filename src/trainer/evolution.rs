@@ -2,8 +2,8 @@ use core::f32;
 use std::{collections::HashMap, sync::{Arc, Mutex}};
 use rayon::{iter::ParallelIterator, slice::ParallelSliceMut};
 
-use crate::neural_network::{activation::{Activation, NetworkActivations}, network::NeatNetwork};
-use super::{config::{mutation::{GenomeMutationProbablities, WeightChangeProbablities}, network_config::NetworkConfig, stop_condition::StopCondition}, fitness::FitnessEvaluator, species::{Species, SPECIES_AVERAGE_SCORE_WINDOW_SIZE}};
+use crate::neural_network::{activation::{Activation, NetworkActivations}, mutation_operator::MutationOperator, network::{InnovationRegistry, NeatNetwork}};
+use super::{config::{mutation::{GaussianWeights, GenomeMutationProbablities, IndependentMutations, WeightBounds, WeightChangeProbablities}, network_config::NetworkConfig, plasticity::PlasticityConfig, ctrnn::CtrnnConfig, fine_tune::FineTuneConfig, cma_es::CmaEsConfig, novelty::NoveltyConfig, multi_objective::MultiObjectiveConfig, phased_search::PhasedSearchConfig, limits::ComplexityLimits, parsimony::ParsimonyConfig, self_adaptation::{Adaptation, MutationRates, SelfAdaptationConfig}, stop_condition::StopCondition}, fitness::FitnessEvaluator, novelty::NoveltyArchive, nsga2, phased_search::{Phase, PhasedSearch}, species::{Species, SPECIES_AVERAGE_SCORE_WINDOW_SIZE}};
 
 const DEFAULT_SPECIES_SIZE: usize = 10;
 
@@ -32,6 +32,28 @@ pub struct EvolutionBuilder<F: FitnessEvaluator> {
     /// higher number the less often we do that). None = no replacement
     replace_worst_every_nth_gen: Option<usize>,
 
+    /// Lamarckian gradient fine tuning, None = off
+    fine_tune: Option<FineTuneConfig>,
+
+    /// CMA-ES weight polishing of elites, None = off
+    cma_es: Option<CmaEsConfig>,
+
+    /// Novelty search, None = select by fitness only
+    novelty: Option<NoveltyConfig>,
+
+    /// NSGA-II selection, None = select by fitness only
+    multi_objective: Option<MultiObjectiveConfig>,
+
+    /// Alternate complexifying and simplifying, None = always complexify
+    phased_search: Option<PhasedSearchConfig>,
+
+    /// Fitness penalty for network size, None = off
+    parsimony: Option<ParsimonyConfig>,
+
+    /// Innovation number and occupied connections shared with
+    /// other evolutions, None = this evolution gets its own
+    innovation_registry: Option<InnovationRegistry>,
+
     network_config: NetworkConfig,
     stop_condition: StopCondition,
 
@@ -51,6 +73,24 @@ pub struct Evolution<F: FitnessEvaluator + Send + Sync> {
     species_size: usize,
     par_chunks_size: usize,
     replace_worst_every_nth_gen: Option<usize>,
+    fine_tune: Option<FineTuneConfig>,
+    cma_es: Option<CmaEsConfig>,
+    novelty: Option<NoveltyConfig>,
+    novelty_archive: Option<NoveltyArchive>,
+    multi_objective: Option<MultiObjectiveConfig>,
+
+    /// Copies of the first Pareto front, taken before the
+    /// networks are mutated
+    pareto_front: Vec<NeatNetwork>,
+    phased_search: Option<PhasedSearch>,
+    parsimony: Option<ParsimonyConfig>,
+
+    /// Multiplier of every mutation rate under the 1/5th
+    /// success rule, see `Adaptation::OneFifthRule`
+    mutation_scale: f32,
+
+    /// Config of every network while complexifying
+    network_config: Arc<NetworkConfig>,
 
     /// To check if we've already got a connection
     /// between two nodes. NEEDS to be (min, max),
@@ -77,7 +117,14 @@ impl<F: FitnessEvaluator + Send + Sync> EvolutionBuilder<F> {
             stop_condition: StopCondition::default(),
             network_config: NetworkConfig::default(),
             par_chunks_size: 1,
-            replace_worst_every_nth_gen: None
+            replace_worst_every_nth_gen: None,
+            fine_tune: None,
+            cma_es: None,
+            novelty: None,
+            multi_objective: None,
+            phased_search: None,
+            parsimony: None,
+            innovation_registry: None,
         }
     }
 
@@ -107,12 +154,68 @@ impl<F: FitnessEvaluator + Send + Sync> EvolutionBuilder<F> {
     pub fn mutation_probabilities(&mut self, prob: GenomeMutationProbablities) -> &mut Self { self.network_config.mutation_probabilities = prob; self }
     /// Set the diffrent mutation probabilities for evolution
     pub fn weight_change_probabilities(&mut self, prob: WeightChangeProbablities) -> &mut Self { self.network_config.weight_change_probabilities = prob; self }
+    /// Adds a custom mutation, picked with `weight` relative to
+    /// `mutation_probabilities` (or fired with probability `weight`
+    /// with independent mutations), see `MutationOperator`
+    pub fn mutation_operator(&mut self, operator: impl MutationOperator + 'static, weight: f32) -> &mut Self {
+        self.network_config.mutation_operators.push((weight, Arc::new(operator)));
+        self
+    }
+    /// Let structural and weight mutations fire independently,
+    /// several per mutation event. Default is None (one mutation
+    /// picked from `mutation_probabilities`)
+    pub fn independent_mutations(&mut self, config: Option<IndependentMutations>) -> &mut Self { self.network_config.independent_mutations = config; self }
+    /// Mutate weights the standard NEAT way, perturbing every
+    /// connection with gaussian noise. Default is None
+    pub fn gaussian_weights(&mut self, config: Option<GaussianWeights>) -> &mut Self { self.network_config.gaussian_weights = config; self }
+    /// Clamp weights after every mutation. Default is None
+    pub fn weight_bounds(&mut self, bounds: Option<WeightBounds>) -> &mut Self { self.network_config.weight_bounds = bounds; self }
 
     /// Every nth generation we'll replace the worst performing
     /// network with the best berforming so it can mutate in diffrent
     /// ways
     pub fn replace_worst_every_nth_gen(&mut self, nth: Option<usize>) -> &mut Self { self.replace_worst_every_nth_gen = nth; self }
 
+    /// Every `config.every_nth_gen` generations, train the weights of
+    /// every network with backpropagation on a supervised dataset and
+    /// write them back into the genome. Default is None
+    pub fn lamarckian_fine_tuning(&mut self, config: Option<FineTuneConfig>) -> &mut Self { self.fine_tune = config; self }
+
+    /// Every `config.every_nth_gen` generations, optimize the weights
+    /// of the best network in every species with CMA-ES against the
+    /// fitness evaluator. Default is None
+    pub fn cma_es_polishing(&mut self, config: Option<CmaEsConfig>) -> &mut Self { self.cma_es = config; self }
+
+    /// Select networks by the novelty of their behaviour (see
+    /// `FitnessEvaluator::evaluate`), or a blend of novelty and
+    /// fitness. Default is None
+    pub fn novelty_search(&mut self, config: Option<NoveltyConfig>) -> &mut Self { self.novelty = config; self }
+
+    /// Select networks with NSGA-II over the objectives of their
+    /// evaluation (see `Evaluation::objectives`), optionally with
+    /// network size as an extra objective. Takes precedence over
+    /// novelty search when selecting. Default is None
+    pub fn multi_objective(&mut self, config: Option<MultiObjectiveConfig>) -> &mut Self { self.multi_objective = config; self }
+
+    /// Switch to a phase of only delete mutations whenever the
+    /// networks grow too much or fitness plateaus, see
+    /// `PhasedSearch`. Default is None
+    pub fn phased_search(&mut self, config: Option<PhasedSearchConfig>) -> &mut Self { self.phased_search = config; self }
+
+    /// Select networks by their fitness minus a penalty
+    /// proportional to their size, see `ParsimonyConfig`.
+    /// Default is None
+    pub fn parsimony(&mut self, config: Option<ParsimonyConfig>) -> &mut Self { self.parsimony = config; self }
+
+    /// Share innovation numbers with other evolutions so that the
+    /// same structural change gets the same innovation number
+    /// everywhere, and networks can move between them (see
+    /// `Archipelago`). Default is None
+    pub fn innovation_registry(&mut self, innovation_number: Arc<Mutex<usize>>, occupied_connections: Arc<Mutex<HashMap<(usize, usize), usize>>>) -> &mut Self {
+        self.innovation_registry = Some((innovation_number, occupied_connections));
+        self
+    }
+
     /// How big each chunk will be when multithreading looping
     /// through all species for running a generation. Default
     /// is 1. The par chunk size is the amount of species one
@@ -127,6 +230,27 @@ impl<F: FitnessEvaluator + Send + Sync> EvolutionBuilder<F> {
     /// to all output nodes, default is true. Bias node not included.
     pub fn preestablish_connections(&mut self, condition: bool) -> &mut Self { self.network_config.initialize_with_connections = condition; self }
 
+    /// Let connections evolve hebbian learning rules which change
+    /// their weights while a network is being evaluated. Default
+    /// is None (static weights)
+    pub fn plasticity(&mut self, config: Option<PlasticityConfig>) -> &mut Self { self.network_config.plasticity = config; self }
+
+    /// Evolve node time constants for continuous time networks,
+    /// see `NeatNetwork::step`. Default is None
+    pub fn ctrnn(&mut self, config: Option<CtrnnConfig>) -> &mut Self { self.network_config.ctrnn = config; self }
+
+    /// Validate every genome after each mutation and panic as soon
+    /// as some internal state gets out of sync. Debug builds only.
+    pub fn validate_after_mutation(&mut self, condition: bool) -> &mut Self { self.network_config.validate_after_mutation = condition; self }
+
+    /// Hard caps on hidden nodes, connections and depth which
+    /// structural mutations won't exceed. Default is no limits
+    pub fn complexity_limits(&mut self, limits: ComplexityLimits) -> &mut Self { self.network_config.limits = limits; self }
+
+    /// Adapt mutation rates and weight step sizes while evolving,
+    /// per genome or for the whole population. Default is None
+    pub fn self_adaptation(&mut self, config: Option<SelfAdaptationConfig>) -> &mut Self { self.network_config.self_adaptation = config; self }
+
     /// This function will run the network trough some test that
     /// the network is trained to do. The function will return an
     /// f32 which evaluates the performance of the network. Higher
@@ -157,8 +281,8 @@ impl<F: FitnessEvaluator + Send + Sync> EvolutionBuilder<F> {
 
         // Create species
         let mut species: Vec<Species> = Vec::with_capacity(batch_size);
-        let global_occupied_connections = Arc::new(Mutex::new(HashMap::new()));
-        let global_innovation_number = Arc::new(Mutex::new(0));
+        let (global_innovation_number, global_occupied_connections) = self.innovation_registry.clone()
+            .unwrap_or_else(|| (Arc::new(Mutex::new(0)), Arc::new(Mutex::new(HashMap::new()))));
 
         for i in 0..batch_size {
             let representative = NeatNetwork::new(
@@ -170,14 +294,18 @@ impl<F: FitnessEvaluator + Send + Sync> EvolutionBuilder<F> {
                 network_config.clone()
             );
 
-            species.push(Species::new(
+            let mut new_species = Species::new(
                 global_innovation_number.clone(),
                 global_occupied_connections.clone(),
                 representative,
                 species_size,
                 i,
                 false
-            ));
+            );
+            new_species.set_novelty_weight(self.novelty.map(|n| n.novelty_weight).unwrap_or(0.0));
+            new_species.set_multi_objective(self.multi_objective.is_some());
+            new_species.set_parsimony(self.parsimony);
+            species.push(new_species);
         }
 
         Evolution {
@@ -189,7 +317,17 @@ impl<F: FitnessEvaluator + Send + Sync> EvolutionBuilder<F> {
             generation: 0,
             species_size,
             par_chunks_size: self.par_chunks_size,
-            replace_worst_every_nth_gen: self.replace_worst_every_nth_gen
+            replace_worst_every_nth_gen: self.replace_worst_every_nth_gen,
+            fine_tune: self.fine_tune.clone(),
+            cma_es: self.cma_es,
+            novelty: self.novelty,
+            novelty_archive: self.novelty.map(|n| NoveltyArchive::new(n.k, n.insertion)),
+            multi_objective: self.multi_objective,
+            pareto_front: Vec::new(),
+            phased_search: self.phased_search.map(PhasedSearch::new),
+            parsimony: self.parsimony,
+            mutation_scale: 1.0,
+            network_config,
         }
     }
 }
@@ -212,12 +350,38 @@ impl<F: FitnessEvaluator + Send + Sync> Evolution<F> {
         let should_stop = Arc::new(Mutex::new(false));
         let should_replace = self.replace_worst_every_nth_gen.is_some();
         
+        // E.g let competitive evaluators pick opponents
+        let population: Vec<&NeatNetwork> = self.species.iter().flat_map(|species| species.networks().iter()).collect();
+        self.fitness_evaluator.lock().unwrap().prepare_generation(&population);
+
+        // Cache fitness in each network 
         self.species.par_chunks_mut(self.par_chunks_size).for_each(|species_chunk| {
             for species in species_chunk {
-                // Cache fitness in each network 
-                // TODO
                 species.generate_fitness(self.fitness_evaluator.clone());
-                
+            }
+        });
+
+        // Judge the previous generation's mutations
+        self.adapt_mutation_rates();
+
+        // Novelty is relative to the whole population, so it can
+        // only be scored once every network has been evaluated
+        if self.novelty_archive.is_some() {
+            self.score_novelty();
+        }
+
+        // Pareto ranks are relative to the whole population too
+        if self.multi_objective.is_some() {
+            self.rank_objectives();
+        }
+
+        // Switch phase before this generation's mutations
+        if self.phased_search.is_some() {
+            self.update_phase();
+        }
+
+        self.species.par_chunks_mut(self.par_chunks_size).for_each(|species_chunk| {
+            for species in species_chunk {
                 // Stop condition
                 let previous_average = species.average_fitness();
                 if self.stop_condition.should_stop(previous_average, self.generation) {
@@ -237,9 +401,24 @@ impl<F: FitnessEvaluator + Send + Sync> Evolution<F> {
                     // species.crossover(self.fitness_function);
                 }
 
+                // Polish the best network before it's copied over the worst
+                if let Some(cma_es) = &self.cma_es {
+                    if self.generation.is_multiple_of(cma_es.every_nth_gen) {
+                        let mut evaluator = self.fitness_evaluator.lock().unwrap().clone();
+                        species.polish_elite(&mut evaluator, cma_es);
+                    }
+                }
+
                 // Mutate
                 species.compute_generation();
 
+                // Lamarckian fine tuning of the next generation
+                if let Some(fine_tune) = &self.fine_tune {
+                    if self.generation.is_multiple_of(fine_tune.every_nth_gen) {
+                        species.fine_tune(fine_tune);
+                    }
+                }
+
                 // Find best and worst
                 if should_replace && self.generation % self.replace_worst_every_nth_gen.unwrap() == 0 {
                     for (net_index, net) in species.networks().iter().enumerate() {
@@ -269,6 +448,105 @@ impl<F: FitnessEvaluator + Send + Sync> Evolution<F> {
         *should_stop.clone().lock().unwrap()
     }
 
+    fn score_novelty(&mut self) {
+        let Some(archive) = &mut self.novelty_archive else { return };
+        let behaviours: Vec<Vec<f32>> = self.species.iter()
+            .flat_map(|species| species.networks().iter().map(|net| net.behaviour().clone()))
+            .collect();
+
+        let mut scores = archive.score_generation(&behaviours).into_iter();
+        for species in self.species.iter_mut() {
+            for network in species.networks_mut() {
+                network.set_novelty(scores.next().unwrap_or(0.0));
+            }
+        }
+    }
+
+    fn rank_objectives(&mut self) {
+        let Some(config) = self.multi_objective else { return };
+        let objectives: Vec<Vec<f32>> = self.species.iter()
+            .flat_map(|species| species.networks().iter().map(|net| config.objectives(net)))
+            .collect();
+
+        let mut ranks = nsga2::rank(&objectives).into_iter();
+        for species in self.species.iter_mut() {
+            for network in species.networks_mut() {
+                let (rank, crowding_distance) = ranks.next().unwrap_or((0, 0.0));
+                network.set_pareto(rank, crowding_distance);
+            }
+        }
+
+        // Mutation comes next and keeps the ranks, so the front
+        // is copied while it still matches the genomes
+        self.pareto_front = self.species.iter()
+            .flat_map(|species| species.networks().iter())
+            .filter(|network| network.pareto_rank() == 0)
+            .cloned()
+            .collect();
+    }
+
+    fn update_phase(&mut self) {
+        let Some(phased_search) = &mut self.phased_search else { return };
+        let complexity = phased_search.config().complexity;
+        let networks = self.species.iter().flat_map(|species| species.networks().iter());
+        let (mut total, mut count, mut best) = (0.0, 0, f32::MIN);
+        for network in networks {
+            total += complexity.of(network) as f32;
+            count += 1;
+            best = best.max(network.previous_fitness());
+        }
+
+        let Some(phase) = phased_search.update(total / count.max(1) as f32, best) else { return };
+        let network_config = match phase {
+            Phase::Complexifying => self.network_config.clone(),
+            // Nothing but deletions, learning rules and time
+            // constants stay as they are too
+            Phase::Simplifying => Arc::new(NetworkConfig {
+                mutation_probabilities: phased_search.config().simplification_mutations,
+                independent_mutations: None,
+                mutation_operators: Vec::new(),
+                plasticity: self.network_config.plasticity.map(|plasticity| PlasticityConfig { mutation_probability: 0, ..plasticity }),
+                ctrnn: self.network_config.ctrnn.map(|ctrnn| CtrnnConfig { mutation_probability: 0, ..ctrnn }),
+                ..(*self.network_config).clone()
+            }),
+        };
+        for species in self.species.iter_mut() {
+            for network in species.networks_mut() {
+                network.set_network_config(network_config.clone());
+            }
+        }
+    }
+
+    /// 1/5th success rule, a mutation succeeded if the network's
+    /// latest fitness beat its fitness before the mutation
+    fn adapt_mutation_rates(&mut self) {
+        let Some(config) = self.network_config.self_adaptation else { return };
+        let Adaptation::OneFifthRule { factor } = config.adaptation else { return };
+
+        let (mut mutated, mut improved) = (0, 0);
+        for network in self.species.iter_mut().flat_map(|species| species.networks_mut().iter_mut()) {
+            let Some(before) = network.take_fitness_before_mutation() else { continue };
+            mutated += 1;
+            if network.previous_fitness() > before { improved += 1; }
+        }
+        if mutated == 0 { return }
+
+        let success = improved as f32 / mutated as f32;
+        if success > 0.2 {
+            self.mutation_scale /= factor;
+        }else if success < 0.2 {
+            self.mutation_scale *= factor;
+        }
+        self.mutation_scale = self.mutation_scale.clamp(config.min_rate, config.max_rate);
+
+        let rates = MutationRates::uniform(self.mutation_scale);
+        for species in self.species.iter_mut() {
+            for network in species.networks_mut() {
+                network.set_mutation_rates(rates);
+            }
+        }
+    }
+
     fn replace_least_fit(&mut self, worst_species: Arc<Mutex<(f32, usize)>>, best_network: Arc<Mutex<(f32, usize, usize)>>) -> () {
         if self.generation % self.replace_worst_every_nth_gen.unwrap() != 0 { return };
 
@@ -285,6 +563,9 @@ impl<F: FitnessEvaluator + Send + Sync> Evolution<F> {
             worst_species.1,
             true
         );
+        self.species[worst_species.1].set_novelty_weight(self.novelty.map(|n| n.novelty_weight).unwrap_or(0.0));
+        self.species[worst_species.1].set_multi_objective(self.multi_objective.is_some());
+        self.species[worst_species.1].set_parsimony(self.parsimony);
     }
 
     pub fn average_fitness(&self) -> f32 {
@@ -298,9 +579,38 @@ impl<F: FitnessEvaluator + Send + Sync> Evolution<F> {
     pub fn species(&self) -> &Vec<Species> {
         &self.species
     }
+    pub(crate) fn species_mut(&mut self) -> &mut Vec<Species> {
+        &mut self.species
+    }
     pub fn get_generation(&self) -> usize {
         self.generation
     }
+    /// Behaviours remembered by novelty search, None if
+    /// novelty search is off
+    pub fn novelty_archive(&self) -> Option<&NoveltyArchive> {
+        self.novelty_archive.as_ref()
+    }
+    /// Copies of the networks which were in the first Pareto
+    /// front when the latest generation was ranked (before they
+    /// were mutated), i.e no other network beat them in every
+    /// objective (see `MultiObjectiveConfig::objectives`).
+    /// Empty if multi-objective selection is off.
+    pub fn pareto_front(&self) -> &Vec<NeatNetwork> {
+        &self.pareto_front
+    }
+    /// Current phase and complexity history, None if
+    /// phased search is off
+    pub fn phased_search(&self) -> Option<&PhasedSearch> {
+        self.phased_search.as_ref()
+    }
+    /// Multiplier of every mutation rate, only changes under
+    /// the 1/5th success rule
+    pub fn mutation_scale(&self) -> f32 {
+        self.mutation_scale
+    }
+    pub fn multi_objective(&self) -> Option<&MultiObjectiveConfig> {
+        self.multi_objective.as_ref()
+    }
     pub fn get_fitness_evaluator(&self) -> Arc<Mutex<F>> {
         self.fitness_evaluator.clone()
     }
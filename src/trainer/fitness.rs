@@ -1,6 +1,34 @@
 /* Imports */
 use super::super::neural_network::network::NeatNetwork;
 
+/// Everything an evaluator reports about one network
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Evaluation {
+    /// Same as what `FitnessEvaluator::run` returns
+    pub fitness: f32,
+
+    /// Describes what the network did rather than how well,
+    /// e.g where the agent ended up. Used by novelty search,
+    /// empty if the evaluator doesn't describe behaviour.
+    pub behaviour: Vec<f32>,
+
+    /// Objectives to maximize at the same time, used by
+    /// multi-objective selection. Empty = only `fitness`.
+    pub objectives: Vec<f32>,
+}
+
+impl Evaluation {
+    pub fn new(fitness: f32, behaviour: Vec<f32>) -> Self {
+        Self { fitness, behaviour, objectives: Vec::new() }
+    }
+
+    /// Adds a fitness vector, e.g `[score, -moves]`
+    pub fn with_objectives(mut self, objectives: Vec<f32>) -> Self {
+        self.objectives = objectives;
+        self
+    }
+}
+
 /// Any struct passed as a fitness evaluator into the
 /// `Evolution` struct needs to implement this trait.
 pub trait FitnessEvaluator: Clone {
@@ -15,6 +43,20 @@ pub trait FitnessEvaluator: Clone {
         // Default behaviour so users who don't care about visualizing
         // won't need to implement this function.
     }
+
+    /// Evaluates the network and describes its behaviour, which
+    /// is what `Evolution` calls. Override this (instead of only
+    /// `run`) to use novelty search.
+    fn evaluate(&mut self, network: &mut NeatNetwork) -> Evaluation {
+        Evaluation::new(self.run(network), Vec::new())
+    }
+
+    /// Called by `Evolution` once per generation before any
+    /// network is evaluated, with the whole population (still
+    /// holding the fitness of the previous generation). Lets
+    /// evaluators which depend on the population, like
+    /// `CompetitiveEvaluator`, pick their opponents.
+    fn prepare_generation(&mut self, _: &[&NeatNetwork]) {}
 }
 
 impl<T> FitnessEvaluator for T
@@ -0,0 +1,175 @@
+/* Imports */
+use std::{collections::HashMap, path::Path, sync::{Arc, Mutex}};
+use rand::{seq::IteratorRandom, thread_rng, Rng};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use crate::neural_network::network::NeatNetwork;
+use super::{config::{map_elites::MapElitesConfig, network_config::NetworkConfig}, fitness::FitnessEvaluator};
+
+/// The fittest network found for one cell of the grid
+#[derive(Clone, Debug)]
+pub struct Elite {
+    pub network: NeatNetwork,
+    pub fitness: f32,
+    pub behaviour: Vec<f32>,
+}
+
+/// MAP-Elites quality diversity search, an alternative to
+/// `Evolution`. Instead of species competing on fitness, the
+/// behaviour space (see `Evaluation::behaviour`) is split into
+/// a grid and every cell keeps the fittest network which
+/// behaved like that. New networks are mutated (and optionally
+/// crossed over) copies of random elites.
+///
+/// ```ignore
+/// let config = MapElitesConfig::new(vec![BehaviourDimension::new(0.0, 1.0, 10); 2]);
+/// let mut map = MapElites::new(2, 1, evaluator, config);
+/// for _ in 0..100 { map.generation(); }
+/// println!("{} {}", map.coverage(), map.qd_score());
+/// ```
+pub struct MapElites<F: FitnessEvaluator + Send + Sync> {
+    input_nodes: usize,
+    output_nodes: usize,
+    fitness_evaluator: F,
+    config: MapElitesConfig,
+    network_config: Arc<NetworkConfig>,
+
+    /// Elites indexed by their bin in every dimension
+    elites: HashMap<Vec<usize>, Elite>,
+    generation: usize,
+
+    global_innovation_number: Arc<Mutex<usize>>,
+    global_occupied_connections: Arc<Mutex<HashMap<(usize, usize), usize>>>,
+}
+
+impl<F: FitnessEvaluator + Send + Sync> MapElites<F> {
+    pub fn new(input_nodes: usize, output_nodes: usize, fitness_evaluator: F, config: MapElitesConfig) -> Self {
+        assert!(!config.dimensions.is_empty(), "MAP-Elites needs at least one behaviour dimension");
+        Self {
+            input_nodes,
+            output_nodes,
+            fitness_evaluator,
+            network_config: Arc::new(config.network_config.clone()),
+            config,
+            elites: HashMap::new(),
+            generation: 0,
+            global_innovation_number: Arc::new(Mutex::new(0)),
+            global_occupied_connections: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Creates, evaluates and places `batch_size` new networks.
+    /// The first generation consists of random networks. Returns
+    /// how many of them became elites.
+    pub fn generation(&mut self) -> usize {
+        let children: Vec<NeatNetwork> = (0..self.config.batch_size).map(|_| self.child()).collect();
+
+        let evaluator = &self.fitness_evaluator;
+        let evaluated: Vec<NeatNetwork> = children.into_par_iter()
+            .map_init(|| Arc::new(Mutex::new(evaluator.clone())), |evaluator, mut network| {
+                network.evaluate_fitness(evaluator.clone());
+                network
+            })
+            .collect();
+
+        self.generation += 1;
+        let mut placed = 0;
+        for network in evaluated {
+            if self.insert(network) { placed += 1; }
+        }
+        placed
+    }
+
+    /// Places `network` (which needs to be evaluated) in its cell
+    /// if the cell is empty or the network is fitter than the elite
+    /// there. Returns true if it was placed.
+    pub fn insert(&mut self, network: NeatNetwork) -> bool {
+        let behaviour = network.behaviour().clone();
+        assert!(
+            behaviour.len() == self.config.dimensions.len(),
+            "Behaviour has {} values but there are {} dimensions, see FitnessEvaluator::evaluate",
+            behaviour.len(), self.config.dimensions.len()
+        );
+
+        let cell = self.cell(&behaviour);
+        let fitness = network.previous_fitness();
+        match self.elites.get(&cell) {
+            Some(elite) if elite.fitness >= fitness => false,
+            _ => {
+                self.elites.insert(cell, Elite { network, fitness, behaviour });
+                true
+            }
+        }
+    }
+
+    /// The cell `behaviour` belongs to
+    pub fn cell(&self, behaviour: &[f32]) -> Vec<usize> {
+        self.config.dimensions.iter().zip(behaviour).map(|(dimension, &value)| dimension.bin(value)).collect()
+    }
+
+    /// A mutated copy, or crossover, of random elites
+    fn child(&self) -> NeatNetwork {
+        let mut rng = thread_rng();
+        let mut child = match self.elites.values().choose(&mut rng) {
+            None => NeatNetwork::new(
+                self.input_nodes,
+                self.output_nodes,
+                self.global_innovation_number.clone(),
+                self.global_occupied_connections.clone(),
+                self.config.activations,
+                self.network_config.clone()
+            ),
+            Some(parent) if rng.gen_bool(self.config.crossover_probability) => {
+                let other = self.elites.values().choose(&mut rng).unwrap();
+                let offspring = parent.network.crossover(&other.network, parent.fitness, other.fitness);
+                if offspring.has_forbidden_cycle() { parent.network.clone() } else { offspring }
+            },
+            Some(parent) => parent.network.clone(),
+        };
+
+        child.mutate();
+        child
+    }
+
+    /// Amount of cells in the grid
+    pub fn cells(&self) -> usize {
+        self.config.dimensions.iter().map(|dimension| dimension.bins).product()
+    }
+
+    /// Fraction (0.0 - 1.0) of cells which hold an elite
+    pub fn coverage(&self) -> f32 {
+        self.elites.len() as f32 / self.cells() as f32
+    }
+
+    /// Summed fitness of every elite, rewards both
+    /// filling the grid and improving its elites
+    pub fn qd_score(&self) -> f32 {
+        self.elites.values().map(|elite| elite.fitness).sum()
+    }
+
+    /// The fittest elite of the grid
+    pub fn best(&self) -> Option<&Elite> {
+        self.elites.values().max_by(|a, b| a.fitness.total_cmp(&b.fitness))
+    }
+
+    /// Saves every elite to `directory`, named by its cell (e.g
+    /// `3_0_7.bin`). They can be loaded with `NeatNetwork::retrieve`.
+    pub fn save(&self, directory: &str) {
+        if let Err(e) = std::fs::create_dir_all(directory) {
+            println!("Cant create {directory}");
+            println!("{e:?}");
+            return;
+        }
+
+        for (cell, elite) in &self.elites {
+            let name = cell.iter().map(|bin| bin.to_string()).collect::<Vec<String>>().join("_");
+            let path = Path::new(directory).join(format!("{name}.bin"));
+            elite.network.save(&path.to_string_lossy());
+        }
+    }
+
+    // Getters
+    pub fn elites(&self) -> &HashMap<Vec<usize>, Elite> { &self.elites }
+    pub fn get_generation(&self) -> usize { self.generation }
+    pub fn config(&self) -> &MapElitesConfig { &self.config }
+    pub fn get_fitness_evaluator(&self) -> &F { &self.fitness_evaluator }
+}
@@ -2,3 +2,12 @@ pub mod evolution;
 pub mod species;
 pub mod config;
 pub mod fitness;
+pub mod cma_es;
+pub mod novelty;
+pub mod map_elites;
+pub mod nsga2;
+pub mod competitive;
+pub mod cooperative;
+pub mod archipelago;
+pub mod real_time;
+pub mod phased_search;
@@ -0,0 +1,78 @@
+/* Imports */
+use rand::{thread_rng, Rng};
+use super::config::novelty::ArchiveInsertion;
+
+/// Remembers behaviours which were novel when they were found.
+/// The novelty of a behaviour is its average distance to the k
+/// nearest behaviours in the archive and the current generation,
+/// so searching for novelty keeps pushing into unexplored parts
+/// of the behaviour space instead of following a deceptive
+/// fitness.
+#[derive(Clone, Debug)]
+pub struct NoveltyArchive {
+    behaviours: Vec<Vec<f32>>,
+    k: usize,
+    insertion: ArchiveInsertion,
+}
+
+impl NoveltyArchive {
+    pub fn new(k: usize, insertion: ArchiveInsertion) -> Self {
+        Self { behaviours: Vec::new(), k, insertion }
+    }
+
+    /// Average distance from `behaviour` to its k nearest neighbours
+    /// among the archive and `others`. 0.0 if there are none.
+    pub fn novelty(&self, behaviour: &[f32], others: &[&Vec<f32>]) -> f32 {
+        let mut distances: Vec<f32> = self.behaviours.iter()
+            .chain(others.iter().copied())
+            .map(|other| distance(behaviour, other))
+            .collect();
+        if distances.is_empty() { return 0.0 }
+
+        distances.sort_by(f32::total_cmp);
+        let k = self.k.min(distances.len()).max(1);
+        distances[..k].iter().sum::<f32>() / k as f32
+    }
+
+    /// Scores every behaviour of a generation against the archive
+    /// and the rest of the generation, then archives the ones which
+    /// should be remembered. Empty behaviours score 0.0 and are
+    /// never archived.
+    pub fn score_generation(&mut self, behaviours: &[Vec<f32>]) -> Vec<f32> {
+        let scores: Vec<f32> = behaviours.iter().enumerate().map(|(index, behaviour)| {
+            if behaviour.is_empty() { return 0.0 }
+            let others: Vec<&Vec<f32>> = behaviours.iter().enumerate()
+                .filter(|(other, b)| *other != index && !b.is_empty())
+                .map(|(_, b)| b)
+                .collect();
+            self.novelty(behaviour, &others)
+        }).collect();
+
+        let mut candidates: Vec<usize> = (0..behaviours.len()).filter(|&i| !behaviours[i].is_empty()).collect();
+        let mut rng = thread_rng();
+        match self.insertion {
+            ArchiveInsertion::Threshold(threshold) => candidates.retain(|&i| scores[i] >= threshold),
+            ArchiveInsertion::Random(probability) => candidates.retain(|_| rng.gen_bool(probability)),
+            ArchiveInsertion::MostNovel(amount) => {
+                candidates.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+                candidates.truncate(amount);
+            },
+        }
+        for index in candidates {
+            self.behaviours.push(behaviours[index].clone());
+        }
+
+        scores
+    }
+
+    // Getters
+    pub fn behaviours(&self) -> &Vec<Vec<f32>> { &self.behaviours }
+    pub fn len(&self) -> usize { self.behaviours.len() }
+    pub fn is_empty(&self) -> bool { self.behaviours.is_empty() }
+}
+
+/// Euclidean distance, behaviours of different lengths
+/// are compared over their common length
+pub fn distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| (a - b).powi(2)).sum::<f32>().sqrt()
+}
@@ -0,0 +1,80 @@
+/// True if `a` is at least as good as `b` in every objective
+/// and better in at least one
+pub fn dominates(a: &[f32], b: &[f32]) -> bool {
+    a.iter().zip(b).all(|(x, y)| x >= y) && a.iter().zip(b).any(|(x, y)| x > y)
+}
+
+/// Splits `objectives` into fronts of indexes. The first front
+/// is not dominated by anything, the second only by the first
+/// front and so on.
+pub fn non_dominated_sort(objectives: &[Vec<f32>]) -> Vec<Vec<usize>> {
+    let mut dominated_by = vec![0; objectives.len()];
+    let mut dominating: Vec<Vec<usize>> = vec![Vec::new(); objectives.len()];
+    for (i, a) in objectives.iter().enumerate() {
+        for (j, b) in objectives.iter().enumerate().skip(i + 1) {
+            if dominates(a, b) {
+                dominating[i].push(j);
+                dominated_by[j] += 1;
+            }else if dominates(b, a) {
+                dominating[j].push(i);
+                dominated_by[i] += 1;
+            }
+        }
+    }
+
+    let mut fronts = Vec::new();
+    let mut front: Vec<usize> = (0..objectives.len()).filter(|&i| dominated_by[i] == 0).collect();
+    while !front.is_empty() {
+        let mut next = Vec::new();
+        for &i in &front {
+            for &j in &dominating[i] {
+                dominated_by[j] -= 1;
+                if dominated_by[j] == 0 { next.push(j); }
+            }
+        }
+        fronts.push(front);
+        front = next;
+    }
+    fronts
+}
+
+/// Crowding distance of every index in `front` (in the same
+/// order), the normalized size of the box between its closest
+/// neighbours in each objective. The extremes of every
+/// objective get infinity so they are always kept.
+pub fn crowding_distance(objectives: &[Vec<f32>], front: &[usize]) -> Vec<f32> {
+    let mut distances = vec![0.0; front.len()];
+    let points: Vec<&Vec<f32>> = front.iter().map(|&i| &objectives[i]).collect();
+    let amount = points.first().map(|point| point.len()).unwrap_or(0);
+    for objective in 0..amount {
+        let values: Vec<f32> = points.iter().map(|point| point[objective]).collect();
+        let mut order: Vec<usize> = (0..front.len()).collect();
+        order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+        let (first, last) = (order[0], order[order.len() - 1]);
+        let range = values[last] - values[first];
+        distances[first] = f32::INFINITY;
+        distances[last] = f32::INFINITY;
+        if range <= 0.0 { continue; }
+
+        for window in order.windows(3) {
+            distances[window[1]] += (values[window[2]] - values[window[0]]) / range;
+        }
+    }
+    distances
+}
+
+/// NSGA-II (Deb et al.) ranking. Returns the Pareto rank (index
+/// of the front) and crowding distance of every objective vector.
+/// Selecting by lowest rank, then highest crowding distance keeps
+/// the whole tradeoff between the objectives alive. Every
+/// objective is maximized.
+pub fn rank(objectives: &[Vec<f32>]) -> Vec<(usize, f32)> {
+    let mut ranks = vec![(0, 0.0); objectives.len()];
+    for (rank, front) in non_dominated_sort(objectives).iter().enumerate() {
+        for (&index, distance) in front.iter().zip(crowding_distance(objectives, front)) {
+            ranks[index] = (rank, distance);
+        }
+    }
+    ranks
+}
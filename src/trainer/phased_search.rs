@@ -0,0 +1,91 @@
+/* Imports */
+use super::config::phased_search::PhasedSearchConfig;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Phase {
+    /// Regular NEAT, networks grow
+    Complexifying,
+
+    /// Only delete mutations, networks shrink
+    Simplifying,
+}
+
+/// Decides when `Evolution` switches between complexifying and
+/// simplifying (Green's phased pruning), so genomes don't keep
+/// bloating without gaining fitness. Fed once per generation
+/// with the mean complexity and best fitness of the population.
+#[derive(Clone)]
+pub struct PhasedSearch {
+    config: PhasedSearchConfig,
+    phase: Phase,
+
+    /// Mean complexity of every generation so far
+    complexity_history: Vec<f32>,
+
+    /// Mean complexity when the last simplification ended
+    complexity_floor: Option<f32>,
+    lowest_complexity: f32,
+    generations_since_drop: usize,
+
+    best_fitness: f32,
+    generations_since_improvement: usize,
+}
+
+impl PhasedSearch {
+    pub fn new(config: PhasedSearchConfig) -> Self {
+        Self {
+            config,
+            phase: Phase::Complexifying,
+            complexity_history: Vec::new(),
+            complexity_floor: None,
+            lowest_complexity: f32::MAX,
+            generations_since_drop: 0,
+            best_fitness: f32::MIN,
+            generations_since_improvement: 0,
+        }
+    }
+
+    /// Records a generation, returns the new phase if it changed
+    pub fn update(&mut self, mean_complexity: f32, best_fitness: f32) -> Option<Phase> {
+        self.complexity_history.push(mean_complexity);
+        let floor = *self.complexity_floor.get_or_insert(mean_complexity);
+
+        if best_fitness > self.best_fitness {
+            self.best_fitness = best_fitness;
+            self.generations_since_improvement = 0;
+        }else {
+            self.generations_since_improvement += 1;
+        }
+
+        match self.phase {
+            Phase::Complexifying => {
+                let bloated = mean_complexity > floor + self.config.complexity_jump;
+                let plateaued = self.config.plateau_generations > 0 && self.generations_since_improvement >= self.config.plateau_generations;
+                if !bloated && !plateaued { return None }
+
+                self.phase = Phase::Simplifying;
+                self.lowest_complexity = mean_complexity;
+                self.generations_since_drop = 0;
+            },
+            Phase::Simplifying => {
+                if mean_complexity < self.lowest_complexity {
+                    self.lowest_complexity = mean_complexity;
+                    self.generations_since_drop = 0;
+                }else {
+                    self.generations_since_drop += 1;
+                }
+                if self.generations_since_drop < self.config.stall_generations { return None }
+
+                self.phase = Phase::Complexifying;
+                self.complexity_floor = Some(mean_complexity);
+                self.generations_since_improvement = 0;
+            },
+        }
+        Some(self.phase)
+    }
+
+    // Getters
+    pub fn phase(&self) -> Phase { self.phase }
+    pub fn complexity_history(&self) -> &Vec<f32> { &self.complexity_history }
+    pub fn config(&self) -> &PhasedSearchConfig { &self.config }
+}
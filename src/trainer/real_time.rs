@@ -0,0 +1,135 @@
+/* Imports */
+use rand::{thread_rng, Rng};
+use crate::neural_network::network::NeatNetwork;
+use super::{config::real_time::RealTimeConfig, evolution::Evolution, fitness::FitnessEvaluator};
+
+/// Where a network was replaced by an offspring, so the
+/// simulation can respawn the agent controlled by it
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Replacement {
+    pub species: usize,
+    pub index: usize,
+}
+
+/// rtNEAT style steady state evolution for networks which live
+/// continuously in the user's own simulation, instead of being
+/// evaluated generation by generation. The simulation reports
+/// fitness whenever it likes and calls `tick` once per step,
+/// and every `replacement_interval` ticks a species is picked
+/// by its average fitness, and its worst network old enough to
+/// be judged is replaced by an offspring of that species.
+/// Networks keep their (species, index) slot for their whole
+/// life.
+///
+/// ```ignore
+/// let mut real_time = RealTimeEvolution::new(evolution, RealTimeConfig::default());
+/// loop {
+///     for (species, index, agent) in agents.iter_mut() {
+///         let output = real_time.network_mut(*species, *index).calculate_output(agent.sense());
+///         real_time.report_fitness(*species, *index, agent.act(output));
+///     }
+///     if let Some(replacement) = real_time.tick() { respawn(replacement); }
+/// }
+/// ```
+pub struct RealTimeEvolution<F: FitnessEvaluator + Send + Sync> {
+    evolution: Evolution<F>,
+    config: RealTimeConfig,
+    ticks: usize,
+}
+
+impl<F: FitnessEvaluator + Send + Sync> RealTimeEvolution<F> {
+    pub fn new(evolution: Evolution<F>, config: RealTimeConfig) -> Self {
+        Self { evolution, config, ticks: 0 }
+    }
+
+    /// Ages every network by one tick and replaces the worst
+    /// eligible network if it's time to
+    pub fn tick(&mut self) -> Option<Replacement> {
+        self.ticks += 1;
+        for species in self.evolution.species_mut() {
+            for network in species.networks_mut() {
+                network.set_age(network.age() + 1);
+            }
+        }
+
+        if self.config.replacement_interval == 0 || !self.ticks.is_multiple_of(self.config.replacement_interval) { return None }
+        self.replace_worst()
+    }
+
+    /// Picks a species by its average fitness and replaces its
+    /// worst network which is at least `minimum_age` ticks old
+    /// with an offspring of the species, so every lineage stays
+    /// in its own species. Only species with such a network are
+    /// picked. None if every network is too young.
+    pub fn replace_worst(&mut self) -> Option<Replacement> {
+        let species = self.evolution.species();
+
+        // (species, index of its worst eligible network, average fitness)
+        let candidates: Vec<(usize, usize, f32)> = species.iter().enumerate().filter_map(|(s, species)| {
+            let (worst, _) = species.networks().iter().enumerate()
+                .filter(|(_, network)| network.age() >= self.config.minimum_age)
+                .min_by(|a, b| a.1.previous_average_fitness().total_cmp(&b.1.previous_average_fitness()))?;
+            let average = species.networks().iter().map(|n| n.previous_average_fitness().max(0.0)).sum::<f32>() / species.networks().len() as f32;
+            Some((s, worst, average))
+        }).collect();
+        if candidates.is_empty() { return None }
+
+        let averages: Vec<f32> = candidates.iter().map(|&(_, _, average)| average).collect();
+        let (target_species, worst_index, _) = candidates[roulette(&averages)];
+        let parents = species[target_species].networks();
+
+        let mut rng = thread_rng();
+        let fitnesses: Vec<f32> = parents.iter().map(|n| n.previous_average_fitness().max(0.0)).collect();
+        let parent = &parents[roulette(&fitnesses)];
+        let mut offspring = if rng.gen_bool(self.config.crossover_probability) {
+            let other = &parents[roulette(&fitnesses)];
+            let offspring = parent.crossover(other, parent.previous_average_fitness(), other.previous_average_fitness());
+            if offspring.has_forbidden_cycle() { parent.clone() } else { offspring }
+        }else {
+            parent.clone()
+        };
+
+        // Offspring start out with the fitness of their parent
+        // until the simulation reports their own
+        let inherited = parent.previous_average_fitness();
+        offspring.mutate();
+        offspring.record_fitness(inherited);
+        offspring.fill_average();
+        offspring.set_age(0);
+
+        self.evolution.species_mut()[target_species].networks_mut()[worst_index] = offspring;
+        Some(Replacement { species: target_species, index: worst_index })
+    }
+
+    /// Records a fitness measured by the simulation, averaged
+    /// with the previously reported ones
+    pub fn report_fitness(&mut self, species: usize, index: usize, fitness: f32) {
+        self.network_mut(species, index).record_fitness(fitness);
+    }
+
+    pub fn network(&self, species: usize, index: usize) -> &NeatNetwork {
+        &self.evolution.species()[species].networks()[index]
+    }
+    pub fn network_mut(&mut self, species: usize, index: usize) -> &mut NeatNetwork {
+        &mut self.evolution.species_mut()[species].networks_mut()[index]
+    }
+
+    // Getters
+    pub fn evolution(&self) -> &Evolution<F> { &self.evolution }
+    pub fn config(&self) -> &RealTimeConfig { &self.config }
+    pub fn ticks(&self) -> usize { self.ticks }
+}
+
+/// Random index, weighted by `weights` (uniform if they sum to 0)
+fn roulette(weights: &[f32]) -> usize {
+    let mut rng = thread_rng();
+    let total: f32 = weights.iter().sum();
+    if total <= 0.0 { return rng.gen_range(0..weights.len()) }
+
+    let mut target = rng.gen_range(0.0..total);
+    for (index, weight) in weights.iter().enumerate() {
+        if target < *weight { return index }
+        target -= weight;
+    }
+    weights.len() - 1
+}
@@ -1,9 +1,9 @@
 /* Imports */
 use std::{collections::HashMap, sync::{Arc, Mutex}};
 use rand::{thread_rng, Rng};
-use crate::neural_network::{average::exponential_average, connection_gene::ConnectionGene, network::NeatNetwork};
+use crate::neural_network::{average::exponential_average, network::NeatNetwork};
 
-use super::fitness::FitnessEvaluator;
+use super::{cma_es, config::{cma_es::CmaEsConfig, fine_tune::FineTuneConfig, parsimony::ParsimonyConfig}, fitness::FitnessEvaluator};
 
 /* Constants */
 pub const SPECIES_AVERAGE_SCORE_WINDOW_SIZE: usize = 12;
@@ -34,6 +34,18 @@ pub struct Species {
     global_occupied_connections: Arc<Mutex<HashMap<(usize, usize), usize>>>,
     name: String,
     index: usize,
+
+    /// How much novelty (instead of fitness) counts when
+    /// selecting networks, see `NoveltyConfig`
+    novelty_weight: f32,
+
+    /// Select by `NeatNetwork::pareto_score` instead, see
+    /// `MultiObjectiveConfig`
+    multi_objective: bool,
+
+    /// Subtracted from the fitness of complex networks when
+    /// selecting, None = off
+    parsimony: Option<ParsimonyConfig>,
 }
 
 impl Species {
@@ -69,19 +81,39 @@ impl Species {
             global_occupied_connections,
             global_innovation_number,
             name: Self::generate_name(),
-            index
+            index,
+            novelty_weight: 0.0,
+            multi_objective: false,
+            parsimony: None,
         }
     }
     pub fn networks(&self) -> & Vec<NeatNetwork> {
         & self.networks
     }
+    pub(crate) fn networks_mut(&mut self) -> &mut Vec<NeatNetwork> {
+        &mut self.networks
+    }
+
+    pub fn set_novelty_weight(&mut self, weight: f32) { self.novelty_weight = weight; }
+    pub fn set_multi_objective(&mut self, multi_objective: bool) { self.multi_objective = multi_objective; }
+    pub fn set_parsimony(&mut self, parsimony: Option<ParsimonyConfig>) { self.parsimony = parsimony; }
+
+    /// What networks are ranked by when selecting, the average
+    /// fitness (minus the parsimony penalty) blended with novelty
+    /// by `novelty_weight`, or the pareto score for multi-objective
+    /// selection
+    pub fn selection_score(&self, network: &NeatNetwork) -> f32 {
+        if self.multi_objective { return network.pareto_score() }
+        let penalty = self.parsimony.map(|p| p.penalty(network)).unwrap_or(0.0);
+        (1.0 - self.novelty_weight) * (network.previous_average_fitness() - penalty) + self.novelty_weight * network.novelty()
+    }
 
     /// Makes every net go trough a fitness function and determines the top 
     /// 30% of all nets. These nets automatically go to the next generation
     /// without changes. The 70% of the rest networks are randomly mutated
     /// and THEN placed in the next generation
     pub fn compute_generation(&mut self) -> () {
-        let scores: Vec<f32> = self.networks.iter().map(|e| e.previous_average_fitness()).collect();
+        let scores: Vec<f32> = self.networks.iter().map(|e| self.selection_score(e)).collect();
 
         // We won't modify the top 30, that's why we only deal with bottom 70 here
         let bottom_70_amount = (self.networks.len() as f32 * 0.7).round() as usize;
@@ -100,6 +132,21 @@ impl Species {
         // }
     }
 
+    /// Trains the weights of every network, see `FineTuneConfig`
+    pub fn fine_tune(&mut self, config: &FineTuneConfig) {
+        for network in self.networks.iter_mut() {
+            network.fine_tune(&config.dataset, config.loss, config.optimizer, config.epochs);
+        }
+    }
+
+    /// Optimizes the weights of the network with the best
+    /// average fitness with CMA-ES, see `cma_es::polish`
+    pub fn polish_elite<F: FitnessEvaluator>(&mut self, fitness_evaluator: &mut F, config: &CmaEsConfig) {
+        let scores: Vec<f32> = self.networks.iter().map(|e| e.previous_average_fitness()).collect();
+        let top_index = Self::top_n_with_indices(&scores, 1)[0];
+        cma_es::polish(&mut self.networks[top_index], fitness_evaluator, config);
+    }
+
     /// Crossover two parents and insert offspring
     pub fn crossover<F: FitnessEvaluator>(&mut self, fitness_evaluator: Arc<Mutex<F>>) -> () {
         assert!(self.networks.len() > 1);
@@ -164,7 +211,7 @@ impl Species {
 
         // TODO I dont know if we should be replacing the worst with offspring but hey
         let mut offspring = self.crossover_networks(networks[0].0, networks[1].0, *networks[0].1, *networks[1].1);
-        if !NeatNetwork::has_cycle(offspring.local_occupied_connections().iter()) {
+        if !offspring.has_forbidden_cycle() {
             offspring.evaluate_fitness(fitness_evaluator);
 
             // Check docs of this method for explanation
@@ -176,64 +223,16 @@ impl Species {
 
     /// Get the offspring of two networks
     pub fn crossover_networks(&self, network1: &NeatNetwork, network2: &NeatNetwork, fitness1: f32, fitness2: f32) -> NeatNetwork {
-        let mut rng = thread_rng();
-        let mut child_genes: Vec<ConnectionGene> = Vec::new();
-    
-        let mut i = 0;
-        let mut j = 0;
-        
-        let net1_genes = network1.get_genes();
-        let net2_genes = network2.get_genes();
-
-        // Traverse both parent genomes
-        while i < net1_genes.len() && j < net2_genes.len() {
-            let gene1 = &net1_genes[i];
-            let gene2 = &net2_genes[j];
-
-            if gene1.innovation_number() == gene2.innovation_number() {
-                // Matching genes: Randomly inherit from either parent
-                if rng.gen() {
-                    child_genes.push(gene1.clone());
-                } else {
-                    child_genes.push(gene2.clone());
-                }
-                i += 1;
-                j += 1;
-            } else {
-                // Disjoint gene from net1_genes
-                if fitness1 >= fitness2 {
-                    child_genes.push(gene1.clone());
-                }else {
-                    child_genes.push(gene2.clone());
-                }
-                i += 1;
-                j += 1;
-            }
-        }
-        
-        // Handle excess genes from the longer genome
-        if fitness1 >= fitness2 {
-            while i < net1_genes.len() {
-                child_genes.push(net1_genes[i].clone());
-                i += 1;
-                j += 1;
-            }
-        } else {
-            while j < net2_genes.len() {
-                child_genes.push(net2_genes[j].clone());
-                i += 1;
-                j += 1;
-            }
-        }
-
-        NeatNetwork::new_with_genes(
+        let mut offspring = NeatNetwork::new_with_genes(
             network1.input_size(), network1.output_size(),
             self.global_innovation_number.clone(),
             self.global_occupied_connections.clone(),
             network1.activations(),
-            child_genes,
+            network1.crossover_genes(network2, fitness1, fitness2),
             network1.network_config()
-        )
+        );
+        offspring.set_mutation_rates(if fitness1 >= fitness2 { network1.mutation_rates() } else { network2.mutation_rates() });
+        offspring
     }
 
     /// Distance
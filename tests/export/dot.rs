@@ -0,0 +1,31 @@
+use std::sync::Arc;
+use neat_algorithm::neural_network::{activation::{Activation, NetworkActivations}, connection_gene::ConnectionGene, network::NeatNetwork};
+
+fn network_with_hidden() -> NeatNetwork {
+    let activations = NetworkActivations::new(Activation::LeakyRelu, Activation::Sigmoid);
+    let mut disabled = ConnectionGene::new(0, 2, 0.5, 0);
+    disabled.set_enabled(false);
+    let genes = vec![
+        disabled, ConnectionGene::new(1, 2, -2., 1),
+        ConnectionGene::new(0, 4, 1., 2), ConnectionGene::new(4, 2, 0.7, 3),
+    ];
+    NeatNetwork::new_with_genes(2, 1, Arc::default(), Arc::default(), activations, genes, Arc::default())
+}
+
+#[test]
+fn ranks() -> () {
+    let dot = network_with_hidden().to_dot();
+    assert!(dot.starts_with("digraph NeatNetwork {"));
+    assert!(dot.contains("{ rank=same; n0 [label=\"I0\""));
+    assert!(dot.contains("n3 [label=\"B\", fillcolor=\"#f4d35e\", shape=square];"));
+    assert!(dot.contains("{ rank=same; n4 [label=\"H4\""));
+    assert!(dot.contains("{ rank=same; n2 [label=\"O2\""));
+}
+
+#[test]
+fn edges() -> () {
+    let dot = network_with_hidden().to_dot();
+    assert!(dot.contains("n0 -> n2 [penwidth=1.08, color=\"#a4afbb\", style=dashed"));
+    assert!(dot.contains("n1 -> n2 [penwidth=2.83, color=\"#b64f5c\", style=solid"));
+    assert!(dot.lines().filter(|l| l.contains("->")).count() == 4);
+}
@@ -0,0 +1,15 @@
+// Generated by neat_algorithm, do not edit.
+#[allow(unused_variables, clippy::all)]
+pub fn champion(input: &[f32; 2]) -> [f32; 2] {
+    let n4 = 1.0f32;
+    let n1 = input[1];
+    let n0 = input[0];
+    let n6 = { let x = 0.1f32 + n1 * 0.123456f32 + n4 * -0.25f32; 1.0 / (1.0 + f32::exp(-x)) };
+    let n5 = { let x = 0.1f32 + n0 * 2.5f32 + n6 * -3.3f32; 1.0 / (1.0 + f32::exp(-x)) };
+    let n3 = 0.1f32 + n5 * -0.75f32 + n6 * 0.001f32;
+    let n2 = 0.1f32 + n0 * 0.35f32 + n1 * -1.2f32 + n4 * 0.5f32;
+    let mut exponent_sum = 0.0f32;
+    exponent_sum += f32::exp(n2);
+    exponent_sum += f32::exp(n3);
+    [f32::exp(n2) / exponent_sum, f32::exp(n3) / exponent_sum]
+}
@@ -0,0 +1,4 @@
+pub mod dot;
+pub mod svg;
+pub mod onnx;
+pub mod rust_source;
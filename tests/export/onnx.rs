@@ -0,0 +1,45 @@
+use std::sync::Arc;
+use neat_algorithm::{export::onnx::OnnxModel, neural_network::{activation::{Activation, NetworkActivations}, network::NeatNetwork}};
+
+fn evolved_network(hidden: Activation, output: Activation) -> NeatNetwork {
+    let activations = NetworkActivations::new(hidden, output);
+    let mut net = NeatNetwork::new(3, 2, Arc::default(), Arc::default(), activations, Arc::default());
+    for _ in 0..300 { net.mutate(); }
+    net
+}
+
+#[test]
+fn round_trip() -> () {
+    let activations = [
+        Activation::Relu, Activation::Linear, Activation::LeakyRelu, Activation::Sigmoid,
+        Activation::Softmax, Activation::Tanh, Activation::Sine, Activation::Gaussian
+    ];
+    for (hidden, output) in activations.iter().zip(activations.iter().rev()) {
+        let mut net = evolved_network(*hidden, *output);
+        let model = net.to_onnx().unwrap();
+        let decoded = OnnxModel::from_bytes(&model.to_bytes()).unwrap();
+        assert!(decoded == model);
+
+        for input in [vec![0.0, 0.0, 0.0], vec![0.5, -1.0, 2.0], vec![-3.0, 0.25, 1.0]] {
+            let expected = net.calculate_output(input.clone());
+            let actual = decoded.evaluate(&input).unwrap();
+            assert!(expected.len() == actual.len());
+            for (e, a) in expected.iter().zip(actual.iter()) {
+                assert!((e - a).abs() <= 1e-4 * e.abs().max(1.0), "{e} != {a}");
+            }
+        }
+    }
+}
+
+#[test]
+fn operators() -> () {
+    let net = evolved_network(Activation::LeakyRelu, Activation::Sigmoid);
+    let model = net.to_onnx().unwrap();
+    let ops: Vec<&str> = model.graph.nodes.iter().map(|n| n.op_type.as_str()).collect();
+
+    assert!(ops.iter().filter(|&&op| op == "Gather").count() == 3);
+    assert!(ops.contains(&"MatMul") && ops.contains(&"Add"));
+    assert!(*ops.last().unwrap() == "Sigmoid");
+    assert!(model.graph.inputs[0].dims == vec![3]);
+    assert!(model.graph.outputs[0].dims == vec![2]);
+}
@@ -0,0 +1,35 @@
+use std::sync::Arc;
+use neat_algorithm::neural_network::{activation::{Activation, NetworkActivations}, connection_gene::ConnectionGene, network::NeatNetwork};
+
+/* Generated by `champion_network().to_rust_source("champion")` */
+include!("generated/champion.rs");
+
+fn champion_network() -> NeatNetwork {
+    let activations = NetworkActivations::new(Activation::Sigmoid, Activation::Softmax);
+    let mut disabled = ConnectionGene::new(1, 3, 0.9, 4);
+    disabled.set_enabled(false);
+    let genes = vec![
+        ConnectionGene::new(0, 2, 0.35, 0), ConnectionGene::new(1, 2, -1.2, 1),
+        ConnectionGene::new(0, 5, 2.5, 2), ConnectionGene::new(5, 3, -0.75, 3),
+        disabled, ConnectionGene::new(1, 6, 0.123456, 5),
+        ConnectionGene::new(6, 5, -3.3, 6), ConnectionGene::new(6, 3, 1e-3, 7),
+        ConnectionGene::new(4, 2, 0.5, 8), ConnectionGene::new(4, 6, -0.25, 9),
+    ];
+    NeatNetwork::new_with_genes(2, 2, Arc::default(), Arc::default(), activations, genes, Arc::default())
+}
+
+#[test]
+fn generated_source() -> () {
+    let source = champion_network().to_rust_source("champion").unwrap();
+    assert!(source == include_str!("generated/champion.rs"));
+}
+
+#[test]
+fn bit_for_bit() -> () {
+    let mut net = champion_network();
+    for input in [[0.0, 0.0], [1.0, -1.0], [0.3, 7.5], [-12.0, 0.001]] {
+        let expected: Vec<u32> = net.calculate_output(input.to_vec()).iter().map(|f| f.to_bits()).collect();
+        let actual: Vec<u32> = champion(&input).iter().map(|f| f.to_bits()).collect();
+        assert!(expected == actual);
+    }
+}
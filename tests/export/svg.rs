@@ -0,0 +1,17 @@
+use std::sync::Arc;
+use neat_algorithm::neural_network::{activation::{Activation, NetworkActivations}, network::NeatNetwork};
+
+#[test]
+fn render() -> () {
+    let activations = NetworkActivations::new(Activation::LeakyRelu, Activation::Sigmoid);
+    let net = NeatNetwork::new(2, 2, Arc::default(), Arc::default(), activations, Arc::default());
+    let svg = net.to_svg(400., 300.);
+
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    /* 2x2 input connections and 2 bias connections */
+    assert!(svg.matches("<line").count() == 6);
+    /* 4 circles and the square bias node */
+    assert!(svg.matches("<circle").count() == 4);
+    assert!(svg.matches("<rect x=").count() == 1);
+}
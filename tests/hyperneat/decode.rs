@@ -0,0 +1,96 @@
+use neat_algorithm::{hyperneat::{evaluator::HyperNeatEvaluator, substrate::Substrate, HyperNeatConfig}, neural_network::{activation::{Activation, NetworkActivations}, builder::NetworkBuilder, network::NeatNetwork}, trainer::fitness::{Evaluation, FitnessEvaluator}};
+
+/// weight = x2 - x1, bias = y2 and (optionally) leo = x1
+fn cppn(leo: bool) -> NeatNetwork {
+    let mut builder = NetworkBuilder::new(NetworkActivations::new(Activation::Linear, Activation::Linear));
+    let (x1, _y1) = (builder.add_input("x1"), builder.add_input("y1"));
+    let (x2, y2) = (builder.add_input("x2"), builder.add_input("y2"));
+    let (weight, bias) = (builder.add_output("weight"), builder.add_output("bias"));
+    builder
+        .set_bias(weight, 0.).set_bias(bias, 0.)
+        .connect(x1, weight, -1.).connect(x2, weight, 1.)
+        .connect(y2, bias, 1.);
+
+    if leo {
+        let leo = builder.add_output("leo");
+        builder.set_bias(leo, 0.).connect(x1, leo, 1.);
+    }
+    builder.build().unwrap()
+}
+
+fn config(leo: bool) -> HyperNeatConfig {
+    HyperNeatConfig {
+        leo,
+        activations: NetworkActivations::new(Activation::Linear, Activation::Linear),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn threshold() -> () {
+    let substrate = Substrate::new(2, Substrate::line(3, -1.), Substrate::line(1, 1.));
+    let mut decoded = config(false).decode(&mut cppn(false), &substrate);
+
+    /* Weights 1, 0 and -1 => 3, not expressed, -3. Bias = y2 = 1 */
+    assert!(decoded.input_size() == 3 && decoded.output_size() == 1);
+    assert!(decoded.get_genes().len() == 2);
+    assert!(decoded.calculate_output(vec![1., 5., 0.]) == vec![4.]);
+    assert!(decoded.calculate_output(vec![0., 0., 1.]) == vec![-2.]);
+}
+
+#[test]
+fn link_expression_output() -> () {
+    let substrate = Substrate::new(2, Substrate::line(3, -1.), Substrate::line(1, 1.));
+    let mut decoded = config(true).decode(&mut cppn(true), &substrate);
+
+    /* Only x1 > 0 is expressed */
+    assert!(decoded.get_genes().len() == 1);
+    assert!(decoded.calculate_output(vec![1., 1., 1.]) == vec![1. - 3.]);
+}
+
+#[test]
+fn hidden_layers() -> () {
+    let mut substrate = Substrate::new(2, Substrate::line(2, -1.), Substrate::line(2, 1.));
+    substrate.with_hidden_layer(Substrate::line(3, 0.)).with_hidden_layer(Vec::new());
+    let decoded = config(false).decode(&mut cppn(false), &substrate);
+
+    assert!(decoded.node_genes().len() == 2 + 2 + 1 + 3);
+    assert!(decoded.validate().is_ok());
+    /* Hidden biases are y2 = 0 */
+    assert!(decoded.node_genes()[5..].iter().all(|n| n.bias() == 0.));
+}
+
+#[test]
+fn evaluator() -> () {
+    let substrate = Substrate::new(2, Substrate::grid(4, 4, 0.), Substrate::line(2, 1.));
+    let mut evaluator = HyperNeatEvaluator::new(substrate, config(false), |net: &mut NeatNetwork| {
+        net.input_size() as f32 + net.output_size() as f32
+    });
+    assert!(evaluator.run(&mut cppn(false)) == 18.);
+}
+
+/// Describes networks by their size and remembers
+/// the population it was prepared with
+#[derive(Clone, Default)]
+struct Sizes { population: Vec<(usize, f32)> }
+impl FitnessEvaluator for Sizes {
+    fn run(&mut self, _: &mut NeatNetwork) -> f32 { 0. }
+    fn evaluate(&mut self, net: &mut NeatNetwork) -> Evaluation {
+        Evaluation::new(1., vec![net.input_size() as f32, net.output_size() as f32])
+    }
+    fn prepare_generation(&mut self, population: &[&NeatNetwork]) {
+        self.population = population.iter().map(|net| (net.input_size(), net.previous_fitness())).collect();
+    }
+}
+
+#[test]
+fn evaluator_forwards() -> () {
+    let substrate = Substrate::new(2, Substrate::grid(4, 4, 0.), Substrate::line(2, 1.));
+    let mut evaluator = HyperNeatEvaluator::new(substrate, config(false), Sizes::default());
+    assert!(evaluator.evaluate(&mut cppn(false)) == Evaluation::new(1., vec![16., 2.]));
+
+    let mut cppn = cppn(false);
+    cppn.record_fitness(3.);
+    evaluator.prepare_generation(&[&cppn]);
+    assert!(evaluator.evaluator().population == vec![(16, 3.)]);
+}
@@ -0,0 +1,63 @@
+use neat_algorithm::{hyperneat::{es::EsHyperNeatConfig, evaluator::HyperNeatEvaluator, substrate::Substrate, HyperNeatConfig}, neural_network::{activation::{Activation, NetworkActivations}, builder::NetworkBuilder, network::NeatNetwork}, trainer::fitness::FitnessEvaluator};
+
+/// weight = tanh(steepness * (x1 + x2)), bias = 0. Sharp
+/// band where the source and target are mirrored in x
+fn cppn(steepness: f32) -> NeatNetwork {
+    let mut builder = NetworkBuilder::new(NetworkActivations::new(Activation::Linear, Activation::Tanh));
+    let (x1, _y1) = (builder.add_input("x1"), builder.add_input("y1"));
+    let (x2, _y2) = (builder.add_input("x2"), builder.add_input("y2"));
+    let (weight, bias) = (builder.add_output("weight"), builder.add_output("bias"));
+    builder
+        .set_bias(weight, 0.).set_bias(bias, 0.)
+        .connect(x1, weight, steepness).connect(x2, weight, steepness);
+    builder.build().unwrap()
+}
+
+fn config() -> EsHyperNeatConfig {
+    EsHyperNeatConfig {
+        hyperneat: HyperNeatConfig {
+            activations: NetworkActivations::new(Activation::Linear, Activation::Linear),
+            ..Default::default()
+        },
+        band_threshold: 0.1,
+        iteration_level: 2,
+        ..Default::default()
+    }
+}
+
+fn substrate() -> Substrate {
+    Substrate::new(2, Substrate::line(1, -1.), Substrate::line(1, 1.))
+}
+
+#[test]
+fn places_hidden_nodes_on_bands() -> () {
+    let mut decoded = config().decode(&mut cppn(3.), &substrate());
+    let hidden = decoded.node_genes().len() - 3;
+
+    assert!(hidden > 0);
+    assert!(decoded.validate().is_ok());
+
+    /* Every kept hidden node lies on a path from the input to the output */
+    for index in 3..decoded.node_genes().len() {
+        let genes = decoded.get_genes();
+        assert!(genes.iter().any(|c| c.node_out() == index));
+        assert!(genes.iter().any(|c| c.node_in() == index));
+    }
+    assert!(decoded.calculate_output(vec![1.]) != decoded.calculate_output(vec![0.]));
+    assert!(decoded.to_dot().contains("n3"));
+}
+
+#[test]
+fn uniform_pattern_has_no_nodes() -> () {
+    let decoded = config().decode(&mut cppn(0.), &substrate());
+    assert!(decoded.node_genes().len() == 3);
+    assert!(decoded.get_genes().is_empty());
+}
+
+#[test]
+fn evaluator() -> () {
+    let mut evaluator = HyperNeatEvaluator::new(substrate(), config(), |net: &mut NeatNetwork| {
+        net.node_genes().len() as f32
+    });
+    assert!(evaluator.run(&mut cppn(3.)) > 3.);
+}
@@ -0,0 +1,3 @@
+pub mod substrate;
+pub mod decode;
+pub mod es;
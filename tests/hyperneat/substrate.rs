@@ -0,0 +1,22 @@
+use neat_algorithm::hyperneat::substrate::Substrate;
+
+#[test]
+fn grid() -> () {
+    let grid = Substrate::grid(3, 2, 0.5);
+    assert!(grid.len() == 6);
+    assert!(grid[0] == [-1., -1., 0.5]);
+    assert!(grid[1] == [0., -1., 0.5]);
+    assert!(grid[5] == [1., 1., 0.5]);
+    assert!(Substrate::line(1, 0.25) == vec![[0., 0.25, 0.]]);
+}
+
+#[test]
+fn query() -> () {
+    let flat = Substrate::new(2, Substrate::line(2, -1.), Substrate::line(1, 1.));
+    assert!(flat.cppn_inputs() == 4);
+    assert!(flat.query(&[1., 2., 3.], &[4., 5., 6.]) == vec![1., 2., 4., 5.]);
+
+    let deep = Substrate::new(3, Vec::new(), Vec::new());
+    assert!(deep.cppn_inputs() == 6);
+    assert!(deep.query(&[1., 2., 3.], &[4., 5., 6.]) == vec![1., 2., 3., 4., 5., 6.]);
+}
@@ -1 +1,4 @@
 mod neural_network;
+mod export;
+mod hyperneat;
+mod trainer;
@@ -0,0 +1,62 @@
+use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use neat_algorithm::neural_network::{activation::{Activation, NetworkActivations}, builder::{BuilderNode, NetworkBuilder}, validation::Violation};
+
+#[test]
+fn build_and_run() -> () {
+    let activations = NetworkActivations::new(Activation::Sigmoid, Activation::Linear);
+    let mut builder = NetworkBuilder::new(activations);
+    let x = builder.add_input("x");
+    let y = builder.add_input("y");
+    let out = builder.add_output("out");
+    let hidden = builder.add_hidden(Activation::Relu, 0.5);
+    builder
+        .connect(x, hidden, 2.)
+        .connect(y, hidden, -1.)
+        .connect(hidden, out, 3.)
+        .connect(BuilderNode::Bias, out, 0.25);
+
+    let mut net = builder.build().unwrap();
+    assert!(builder.node("y") == Some(BuilderNode::Input(1)));
+    assert!(builder.index_of(hidden) == 4);
+    assert!(net.node_genes()[4].activation_function() == Some(Activation::Relu));
+
+    /* relu(0.5 + 2 * 1 - 1 * 4) = 0 => 0.1 + 0.25 */
+    assert!(net.calculate_output(vec![1., 4.]) == vec![0.1 + 0.25]);
+    /* relu(0.5 + 2 * 1) = 2.5 => 0.1 + 7.5 + 0.25 */
+    assert!(net.calculate_output(vec![1., 0.]) == vec![0.1 + 7.5 + 0.25]);
+}
+
+#[test]
+fn shared_innovations() -> () {
+    let innovation = Arc::new(Mutex::new(0));
+    let occupied = Arc::new(Mutex::new(HashMap::new()));
+    let mut first = NetworkBuilder::new(NetworkActivations::default());
+    first.with_registry(innovation.clone(), occupied.clone());
+    first.add_input("a");
+    first.add_output("b");
+    first.connect_named("a", "b", 0.5);
+
+    let mut second = NetworkBuilder::new(NetworkActivations::default());
+    second.with_registry(innovation.clone(), occupied.clone());
+    let (a, b) = (second.add_input("a"), second.add_output("b"));
+    second.connect(BuilderNode::Bias, b, 1.).connect(a, b, 1.);
+
+    let first = first.build().unwrap();
+    let second = second.build().unwrap();
+    assert!(first.get_genes()[0].innovation_number() == 0);
+    assert!(second.get_genes()[0].innovation_number() == 1);
+    assert!(second.get_genes()[1].innovation_number() == 0);
+    assert!(*innovation.lock().unwrap() == 1);
+}
+
+#[test]
+fn invalid_topology() -> () {
+    let mut builder = NetworkBuilder::new(NetworkActivations::default());
+    let out = builder.add_output("out");
+    let (h1, h2) = (builder.add_hidden(Activation::Relu, 0.), builder.add_hidden(Activation::Relu, 0.));
+    builder.connect(h1, h2, 1.).connect(h2, h1, 1.).connect(h2, out, 1.);
+
+    let violations = builder.build().err().unwrap();
+    assert!(violations.contains(&Violation::Cycle));
+}
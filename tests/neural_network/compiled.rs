@@ -0,0 +1,16 @@
+use std::sync::Arc;
+use neat_algorithm::neural_network::{activation::{Activation, NetworkActivations}, compiled::{CompiledNetwork, CompiledNodeKind}, network::NeatNetwork};
+
+#[test]
+fn matches_calculate_output() -> () {
+    let activations = NetworkActivations::new(Activation::LeakyRelu, Activation::Softmax);
+    let mut net = NeatNetwork::new(2, 3, Arc::default(), Arc::default(), activations, Arc::default());
+    for _ in 0..300 { net.mutate(); }
+
+    let compiled = CompiledNetwork::new(&net).unwrap();
+    assert!(compiled.nodes().len() == net.node_genes().len());
+    assert!(matches!(compiled.nodes().iter().find(|n| n.index == 0).unwrap().kind, CompiledNodeKind::Input(0)));
+    for input in [vec![0.0, 1.0], vec![-0.5, 3.0]] {
+        assert!(compiled.evaluate(&input) == net.calculate_output(input));
+    }
+}
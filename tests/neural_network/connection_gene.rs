@@ -1,4 +1,4 @@
-use neat_algorithm::{neural_network::connection_gene::ConnectionGene, trainer::config::mutation::WeightChangeProbablities};
+use neat_algorithm::{neural_network::connection_gene::ConnectionGene, trainer::config::mutation::{GaussianWeights, WeightBounds, WeightChangeProbablities}};
 
 #[test]
 fn initialize() -> () {
@@ -47,3 +47,35 @@ fn mutate() -> () {
         random happens to to change nothing */
     assert!(conn.weight() != weight);
 }
+
+#[test]
+fn gaussian() -> () {
+    let mut conn = ConnectionGene::new(0, 1, 0.5, 0);
+
+    /* Never touched */
+    let untouched = GaussianWeights { perturb_probability: 0.0, replace_probability: 0.0, ..Default::default() };
+    conn.mutate_weight_gaussian(&untouched, 1.0);
+    assert!(conn.weight() == 0.5);
+
+    /* Always perturbed, but not with a zero step size */
+    let perturb = GaussianWeights { perturb_probability: 1.0, replace_probability: 0.0, ..Default::default() };
+    conn.mutate_weight_gaussian(&perturb, 0.0);
+    assert!(conn.weight() == 0.5);
+    conn.mutate_weight_gaussian(&perturb, 1.0);
+    assert!(conn.weight() != 0.5);
+
+    /* Always replaced, a zero sigma means zero */
+    let replace = GaussianWeights { perturb_probability: 0.0, replace_probability: 1.0, replace_sigma: 0.0, ..Default::default() };
+    conn.mutate_weight_gaussian(&replace, 1.0);
+    assert!(conn.weight() == 0.0);
+}
+
+#[test]
+fn clamp() -> () {
+    let mut conn = ConnectionGene::new(0, 1, 12.0, 0);
+    conn.clamp_weight(&WeightBounds { min: -1.0, max: 1.0 });
+    assert!(conn.weight() == 1.0);
+    conn.set_weight(-3.0);
+    conn.clamp_weight(&WeightBounds { min: -1.0, max: 1.0 });
+    assert!(conn.weight() == -1.0);
+}
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+use neat_algorithm::{neural_network::{activation::{Activation, NetworkActivations}, builder::NetworkBuilder, ctrnn::Integration, network::NeatNetwork}, trainer::config::{ctrnn::CtrnnConfig, mutation::GenomeMutationProbablities, network_config::NetworkConfig}};
+
+/// dy/dt = x - y
+fn leaky_integrator(config: NetworkConfig) -> NeatNetwork {
+    let mut builder = NetworkBuilder::new(NetworkActivations::new(Activation::Linear, Activation::Linear));
+    builder.with_network_config(Arc::new(config));
+    let (x, out) = (builder.add_input("x"), builder.add_output("out"));
+    builder.set_bias(out, 0.).connect(x, out, 1.);
+    builder.build().unwrap()
+}
+
+#[test]
+fn euler() -> () {
+    let mut net = leaky_integrator(NetworkConfig::default());
+    assert!(net.step(vec![1.], 0.5) == vec![0.5]);
+    assert!(net.step(vec![1.], 0.5) == vec![0.75]);
+
+    /* Twice as slow */
+    net.reset_state();
+    net.set_time_constant(1, 2.);
+    assert!(net.step(vec![1.], 0.5) == vec![0.25]);
+
+    /* Instantaneous output is unaffected */
+    assert!(net.calculate_output(vec![1.]) == vec![1.]);
+}
+
+#[test]
+fn runge_kutta() -> () {
+    let mut net = leaky_integrator(NetworkConfig::default());
+    let exact = 1. - f32::exp(-0.5);
+    let euler = net.step_with(vec![1.], 0.5, Integration::Euler)[0];
+    net.reset_state();
+    let rk4 = net.step_with(vec![1.], 0.5, Integration::RungeKutta4)[0];
+
+    assert!((rk4 - exact).abs() < 1e-3);
+    assert!((rk4 - exact).abs() < (euler - exact).abs());
+
+    /* Integration from the config */
+    let mut net = leaky_integrator(NetworkConfig {
+        ctrnn: Some(CtrnnConfig { integration: Integration::RungeKutta4, ..Default::default() }),
+        ..Default::default()
+    });
+    assert!(net.step(vec![1.], 0.5)[0] == rk4);
+}
+
+#[test]
+fn mutate_time_constant() -> () {
+    let mut net = leaky_integrator(NetworkConfig {
+        mutation_probabilities: GenomeMutationProbablities {
+            split_connection: 0, create_connection: 0, change_weight: 0, toggle_weight: 0, delete_connection: 0, delete_node: 0, nothing: 0
+        },
+        ctrnn: Some(CtrnnConfig { time_constant_step: 0.5, min_time_constant: 0.8, ..Default::default() }),
+        ..Default::default()
+    });
+
+    for _ in 0..50 {
+        net.mutate();
+        let time_constant = net.node_genes()[1].time_constant();
+        assert!(time_constant >= 0.8);
+    }
+    assert!(net.node_genes()[1].time_constant() != 1.);
+    assert!(net.node_genes()[0].time_constant() == 1. && net.node_genes()[2].time_constant() == 1.);
+}
+
+/// x -> hidden -> out, all weights 1
+fn relay(config: NetworkConfig) -> NeatNetwork {
+    let mut builder = NetworkBuilder::new(NetworkActivations::new(Activation::Linear, Activation::Linear));
+    builder.with_network_config(Arc::new(config));
+    let (x, out) = (builder.add_input("x"), builder.add_output("out"));
+    let hidden = builder.add_hidden(Activation::Linear, 0.);
+    builder.set_bias(out, 0.).connect(x, hidden, 1.).connect(hidden, out, 1.);
+    builder.build().unwrap()
+}
+
+fn recurrent() -> NetworkConfig {
+    NetworkConfig { ctrnn: Some(CtrnnConfig { recurrent: true, mutation_probability: 0, ..Default::default() }), ..Default::default() }
+}
+
+#[test]
+fn self_loop() -> () {
+    assert!(!relay(NetworkConfig::default()).add_connection(3, 3, 0.5));
+
+    /* The hidden node settles at x / (1 - 0.5) */
+    let mut net = relay(recurrent());
+    assert!(net.add_connection(3, 3, 0.5));
+    let mut output = 0.;
+    for _ in 0..300 { output = net.step(vec![1.], 0.1)[0]; }
+    assert!((output - 2.).abs() < 1e-3);
+}
+
+#[test]
+fn mutate_recurrent() -> () {
+    let mut config = recurrent();
+    config.mutation_probabilities = GenomeMutationProbablities { split_connection: 1, create_connection: 3, change_weight: 0, toggle_weight: 0, delete_connection: 0, delete_node: 0, nothing: 0 };
+    let mut net = relay(config);
+
+    for _ in 0..100 {
+        net.mutate();
+        net.step(vec![1.], 0.1);
+        net.calculate_output(vec![1.]);
+    }
+    assert!(NeatNetwork::has_cycle(net.local_occupied_connections().iter()));
+}
+
+#[test]
+fn split_in_cycle() -> () {
+    let mut config = recurrent();
+    config.mutation_probabilities = GenomeMutationProbablities { split_connection: 1, create_connection: 0, change_weight: 0, toggle_weight: 0, delete_connection: 0, delete_node: 0, nothing: 0 };
+    let mut net = relay(config);
+    assert!(net.add_connection(3, 3, 0.5));
+
+    /* The new node always passes on to the old connection's target */
+    for _ in 0..50 {
+        net.mutate();
+        let node = net.node_genes().len() - 1;
+        assert!(net.get_genes().iter().any(|gene| gene.node_in() == node && gene.enabled()));
+    }
+}
+
+#[test]
+fn crossover_keeps_nodes() -> () {
+    let mut builder = NetworkBuilder::new(NetworkActivations::new(Activation::Linear, Activation::Linear));
+    let (x, out) = (builder.add_input("x"), builder.add_output("out"));
+    let hidden = builder.add_hidden(Activation::Sigmoid, 0.7);
+    builder.set_bias(out, -2.).connect(x, hidden, 1.).connect(hidden, out, 1.);
+    let mut net = builder.build().unwrap();
+    net.set_time_constant(3, 3.);
+
+    let mut child = net.crossover(&net, 1., 1.);
+    assert!(child.calculate_output(vec![1.]) == net.calculate_output(vec![1.]));
+    let (parent, offspring) = (&net.node_genes()[1..], &child.node_genes()[1..]);
+    assert!(parent.iter().zip(offspring).all(|(a, b)| {
+        a.bias() == b.bias() && a.time_constant() == b.time_constant() && a.activation_function() == b.activation_function()
+    }));
+
+    /* The fitter parent's nodes win */
+    let mut other = net.clone();
+    other.set_time_constant(3, 5.);
+    assert!(net.crossover(&other, 1., 2.).node_genes()[3].time_constant() == 5.);
+    assert!(net.crossover(&other, 2., 1.).node_genes()[3].time_constant() == 3.);
+}
@@ -0,0 +1,74 @@
+use neat_algorithm::neural_network::{activation::{Activation, NetworkActivations}, builder::{BuilderNode, NetworkBuilder}, gradient::{Loss, Optimizer}, network::NeatNetwork};
+
+const WEIGHTS: [f32; 7] = [0.5, -0.8, 1.2, 0.3, -0.6, 0.9, 0.4];
+
+/// Two inputs, two hidden nodes (one with its own activation)
+/// and two outputs
+fn network(activations: NetworkActivations, weights: &[f32]) -> NeatNetwork {
+    let mut builder = NetworkBuilder::new(activations);
+    let (x, y) = (builder.add_input("x"), builder.add_input("y"));
+    let (a, b) = (builder.add_output("a"), builder.add_output("b"));
+    let h1 = builder.add_hidden(activations.hidden, 0.2);
+    let h2 = builder.add_hidden(Activation::Tanh, -0.1);
+    builder
+        .connect(x, h1, weights[0]).connect(y, h1, weights[1])
+        .connect(x, h2, weights[2]).connect(h1, h2, weights[3])
+        .connect(h1, a, weights[4]).connect(h2, b, weights[5])
+        .connect(BuilderNode::Bias, a, weights[6]);
+    builder.build().unwrap()
+}
+
+#[test]
+fn matches_finite_differences() -> () {
+    let cases = [
+        (Activation::Sigmoid, Activation::Linear, Loss::MeanSquaredError),
+        (Activation::Tanh, Activation::Sigmoid, Loss::MeanSquaredError),
+        (Activation::Sine, Activation::Softmax, Loss::CrossEntropy),
+        (Activation::Gaussian, Activation::Tanh, Loss::MeanSquaredError),
+        (Activation::LeakyRelu, Activation::Sigmoid, Loss::CrossEntropy),
+    ];
+    let (input, target) = ([0.7, -0.4], [0.2, 0.8]);
+    let epsilon = 1e-3;
+
+    for (hidden, output, loss) in cases {
+        let activations = NetworkActivations::new(hidden, output);
+        let net = network(activations, &WEIGHTS);
+        let gradient = net.gradient(&input, &target, loss).unwrap();
+        let mut out = net.clone();
+        assert!(gradient.loss == loss.loss(&out.calculate_output(input.to_vec()), &target));
+
+        for i in 0..WEIGHTS.len() {
+            let (mut plus, mut minus) = (WEIGHTS, WEIGHTS);
+            plus[i] += epsilon;
+            minus[i] -= epsilon;
+            let loss_plus = loss.loss(&network(activations, &plus).calculate_output(input.to_vec()), &target);
+            let loss_minus = loss.loss(&network(activations, &minus).calculate_output(input.to_vec()), &target);
+            let numeric = (loss_plus - loss_minus) / (2. * epsilon);
+            assert!((numeric - gradient.weights[i]).abs() < 1e-2, "{hidden:?} {output:?} weight {i}: {numeric} != {}", gradient.weights[i]);
+        }
+
+        /* Inputs and the bias node have no bias to train */
+        assert!(gradient.biases[..2].iter().all(|b| *b == 0.) && gradient.biases[4] == 0.);
+    }
+}
+
+#[test]
+fn fine_tune() -> () {
+    let dataset: Vec<(Vec<f32>, Vec<f32>)> = (0..10)
+        .map(|i| i as f32 / 10.)
+        .map(|x| (vec![x], vec![2. * x + 1.]))
+        .collect();
+
+    for optimizer in [Optimizer::sgd(0.1), Optimizer::adam(0.05)] {
+        let mut builder = NetworkBuilder::new(NetworkActivations::new(Activation::Linear, Activation::Linear));
+        let (x, out) = (builder.add_input("x"), builder.add_output("out"));
+        builder.set_bias(out, 0.).connect(x, out, 0.);
+        let mut net = builder.build().unwrap();
+
+        let first = net.fine_tune(&dataset, Loss::MeanSquaredError, optimizer, 1).unwrap();
+        let last = net.fine_tune(&dataset, Loss::MeanSquaredError, optimizer, 300).unwrap();
+        assert!(last < first && last < 1e-3, "{optimizer:?}: {first} -> {last}");
+        assert!((net.get_genes()[0].weight() - 2.).abs() < 0.1);
+        assert!((net.node_genes()[1].bias() - 1.).abs() < 0.1);
+    }
+}
@@ -1,4 +1,12 @@
 pub mod activation;
+pub mod builder;
+pub mod compiled;
 pub mod connection_gene;
+pub mod ctrnn;
+pub mod gradient;
+pub mod mutation_operator;
+pub mod mutation_record;
 pub mod network;
 pub mod node_gene;
+pub mod plasticity;
+pub mod validation;
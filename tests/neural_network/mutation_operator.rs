@@ -0,0 +1,74 @@
+use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+use rand::{rngs::ThreadRng, thread_rng, Rng};
+use neat_algorithm::{neural_network::{activation::NetworkActivations, mutation_operator::{MutationOperator, SplitConnection}, network::{InnovationRegistry, NeatNetwork}}, trainer::{config::{mutation::GenomeMutationProbablities, network_config::NetworkConfig}, evolution::Evolution}};
+
+static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+/// Connects the last input to a random hidden node
+struct ConnectSensor;
+impl MutationOperator for ConnectSensor {
+    fn mutate(&self, network: &mut NeatNetwork, rng: &mut ThreadRng, _: &InnovationRegistry) {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+        let hidden_start = network.input_size() + network.output_size() + 1;
+        if network.node_genes().len() == hidden_start { return }
+        let hidden = rng.gen_range(hidden_start..network.node_genes().len());
+        network.add_connection(network.input_size() - 1, hidden, 0.5);
+    }
+}
+
+fn only(operator: impl MutationOperator + 'static) -> NetworkConfig {
+    let mut config = NetworkConfig::default();
+    config.mutation_probabilities = GenomeMutationProbablities { split_connection: 0, create_connection: 0, change_weight: 0, toggle_weight: 0, delete_connection: 0, delete_node: 0, nothing: 0 };
+    config.mutation_operators.push((1.0, Arc::new(operator)));
+    config.validate_after_mutation = true;
+    config
+}
+
+#[test]
+fn custom_operator() -> () {
+    let mut net = NeatNetwork::new(3, 1, Arc::default(), Arc::default(), NetworkActivations::default(), Arc::new(only(ConnectSensor)));
+    let registry = net.innovation_registry();
+    SplitConnection.mutate(&mut net, &mut thread_rng(), &registry);
+    assert!(net.node_genes().len() == 6);
+
+    let before = CALLS.load(Ordering::SeqCst);
+    net.mutate();
+    assert!(CALLS.load(Ordering::SeqCst) == before + 1);
+    assert!(net.get_genes().iter().any(|gene| gene.node_in() == 2 && gene.node_out() == 5));
+}
+
+#[test]
+fn add_connection() -> () {
+    let mut net = NeatNetwork::new(2, 1, Arc::default(), Arc::default(), NetworkActivations::default(), Arc::new(only(ConnectSensor)));
+    let registry = net.innovation_registry();
+    SplitConnection.mutate(&mut net, &mut thread_rng(), &registry);
+    let hidden = 4;
+
+    assert!(!net.add_connection(0, 2, 1.0)); // Exists
+    assert!(!net.add_connection(2, hidden, 1.0)); // From output
+    assert!(!net.add_connection(hidden, 0, 1.0)); // To input
+    assert!(!net.add_connection(hidden, 3, 1.0)); // To bias
+    assert!(!net.add_connection(hidden, 9, 1.0)); // No such node
+
+    let split = net.get_genes().iter().find(|gene| gene.node_out() == hidden).unwrap().node_in();
+    for node in [0, 1, 3].into_iter().filter(|&node| node != split) {
+        assert!(net.add_connection(node, hidden, 1.0));
+    }
+    assert!(!net.add_connection(split, hidden, 1.0));
+    assert!(net.validate().is_ok());
+}
+
+#[test]
+fn register() -> () {
+    let evolution = Evolution::new()
+        .batch_size(1)
+        .with_input_nodes(2)
+        .with_output_nodes(1)
+        .mutation_operator(ConnectSensor, 3.0)
+        .set_fitness_evaluator(1.)
+        .build();
+
+    let config = evolution.species()[0].networks()[0].network_config();
+    assert!(config.mutation_operators.len() == 1);
+    assert!(config.mutation_operators[0].0 == 3.0);
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+use neat_algorithm::{neural_network::{activation::NetworkActivations, mutation_record::Change, network::NeatNetwork}, trainer::config::{ctrnn::CtrnnConfig, mutation::{GenomeMutationProbablities, IndependentMutations, WeightBounds}, network_config::NetworkConfig, plasticity::PlasticityConfig, self_adaptation::SelfAdaptationConfig}};
+
+/// Every kind of mutation if `everything`, otherwise only
+/// the ones in `mutation_probabilities`
+fn network(mutation_probabilities: GenomeMutationProbablities, everything: bool) -> NeatNetwork {
+    let mut config = NetworkConfig::default();
+    config.mutation_probabilities = mutation_probabilities;
+    if everything {
+        config.plasticity = Some(PlasticityConfig::default());
+        config.ctrnn = Some(CtrnnConfig::default());
+        config.weight_bounds = Some(WeightBounds::default());
+        config.self_adaptation = Some(SelfAdaptationConfig::default());
+    }
+    config.validate_after_mutation = true;
+    NeatNetwork::new(3, 2, Arc::default(), Arc::default(), NetworkActivations::default(), Arc::new(config))
+}
+
+fn same(a: &NeatNetwork, b: &NeatNetwork) -> bool {
+    let genes = a.get_genes().len() == b.get_genes().len() && a.get_genes().iter().zip(b.get_genes()).all(|(a, b)| {
+        a.node_in() == b.node_in() && a.node_out() == b.node_out() && a.weight() == b.weight()
+            && a.enabled() == b.enabled() && a.innovation_number() == b.innovation_number() && a.plasticity() == b.plasticity()
+    });
+    let nodes = a.node_genes().len() == b.node_genes().len() && a.node_genes().iter().zip(b.node_genes()).all(|(a, b)| a.time_constant() == b.time_constant());
+    genes && nodes
+        && a.local_occupied_connections() == b.local_occupied_connections()
+        && a.get_highest_local_innovation() == b.get_highest_local_innovation()
+        && a.mutation_rates() == b.mutation_rates()
+        && a.fitness_before_mutation() == b.fitness_before_mutation()
+}
+
+#[test]
+fn revert() -> () {
+    let mut net = network(GenomeMutationProbablities { split_connection: 20, create_connection: 20, change_weight: 20, toggle_weight: 10, delete_connection: 5, delete_node: 5, nothing: 5 }, true);
+    for i in 0..400 {
+        let before = net.clone();
+        let record = net.mutate();
+
+        /* Keep every third mutation so the genome grows */
+        if i % 3 == 0 { continue }
+        net.revert(record);
+        assert!(same(&net, &before));
+        assert!(net.validate().is_ok());
+        assert!(net.calculate_output(vec![0.5, -1., 2.]) == before.clone().calculate_output(vec![0.5, -1., 2.]));
+    }
+}
+
+#[test]
+fn revert_independent() -> () {
+    let mut net = network(GenomeMutationProbablities::default(), true);
+    let mut config = (*net.network_config()).clone();
+    config.independent_mutations = Some(IndependentMutations { split_connection: 0.5, create_connection: 0.5, toggle_weight: 0.3, delete_connection: 0.2, delete_node: 0.2, weight_mutations: 3.0, ..Default::default() });
+    net.set_network_config(Arc::new(config));
+    for _ in 0..100 { net.mutate(); }
+
+    for _ in 0..100 {
+        let before = net.clone();
+        let record = net.mutate();
+        net.revert(record);
+        assert!(same(&net, &before));
+    }
+}
+
+#[test]
+fn describes_split() -> () {
+    let mut net = network(GenomeMutationProbablities { split_connection: 1, create_connection: 0, change_weight: 0, toggle_weight: 0, delete_connection: 0, delete_node: 0, nothing: 0 }, false);
+    let record = net.mutate();
+    let changes = record.changes();
+    assert!(changes.len() == 4);
+    assert!(matches!(changes[0], Change::Toggle { old: true, new: false, .. }));
+    assert!(matches!(changes[1], Change::NodeAdded { node: 6, .. }));
+    assert!(matches!(changes[2], Change::ConnectionAdded { index: 8 }));
+    assert!(matches!(changes[3], Change::ConnectionAdded { index: 9 }));
+
+    net.revert(record);
+    assert!(net.node_genes().len() == 6);
+    assert!(net.get_genes().iter().all(|gene| gene.enabled()));
+}
+
+#[test]
+fn nothing() -> () {
+    let mut net = network(GenomeMutationProbablities { split_connection: 0, create_connection: 0, change_weight: 0, toggle_weight: 0, delete_connection: 0, delete_node: 0, nothing: 1 }, false);
+    assert!(net.mutate().is_empty());
+}
@@ -1,5 +1,5 @@
 use std::sync::{Arc, Mutex};
-use neat_algorithm::{neural_network::{activation::{Activation, NetworkActivations}, connection_gene::ConnectionGene, network::NeatNetwork}, trainer::fitness::FitnessEvaluator};
+use neat_algorithm::{neural_network::{activation::{Activation, NetworkActivations}, connection_gene::ConnectionGene, mutation_record::Change, network::NeatNetwork}, trainer::{config::{mutation::{GaussianWeights, GenomeMutationProbablities, IndependentMutations, WeightBounds}, network_config::NetworkConfig}, fitness::FitnessEvaluator}};
 
 #[test]
 fn initialize_default() -> () {
@@ -51,3 +51,56 @@ fn fitness() -> () {
     net.evaluate_fitness(Arc::new(Mutex::new(FitnessEval)));
     assert!(net.previous_fitness() == 1.);
 }
+
+#[test]
+fn gaussian_weights() -> () {
+    let mut config = NetworkConfig::default();
+    config.mutation_probabilities = GenomeMutationProbablities { change_weight: 1, split_connection: 0, create_connection: 0, toggle_weight: 0, delete_connection: 0, delete_node: 0, nothing: 0 };
+    config.gaussian_weights = Some(GaussianWeights { perturb_probability: 1.0, replace_probability: 0.0, sigma: 5.0, ..Default::default() });
+    config.weight_bounds = Some(WeightBounds { min: -2.0, max: 2.0 });
+    let mut net = NeatNetwork::new(3, 2, Arc::default(), Arc::default(), NetworkActivations::default(), Arc::new(config));
+    let before: Vec<f32> = net.get_genes().iter().map(|gene| gene.weight()).collect();
+
+    /* One mutation moves every weight */
+    net.mutate();
+    assert!(net.get_genes().iter().zip(before).all(|(gene, weight)| gene.weight() != weight));
+    for _ in 0..50 { net.mutate(); }
+    assert!(net.get_genes().iter().all(|gene| (-2.0..=2.0).contains(&gene.weight())));
+}
+
+#[test]
+fn independent_mutations() -> () {
+    let structural = IndependentMutations { split_connection: 1.0, create_connection: 1.0, toggle_weight: 0.0, weight_mutations: 0.0, ..Default::default() };
+    let mut config = NetworkConfig::default();
+    config.independent_mutations = Some(structural);
+    config.validate_after_mutation = true;
+    let mut net = NeatNetwork::new(3, 2, Arc::default(), Arc::default(), NetworkActivations::default(), Arc::new(config.clone()));
+    let weights: Vec<f32> = net.get_genes().iter().map(|gene| gene.weight()).collect();
+
+    /* Split and create in the same event, weights untouched */
+    net.mutate();
+    assert!(net.node_genes().len() == 7);
+    assert!(net.get_genes().len() >= 10);
+    assert!(net.get_genes().iter().zip(weights).all(|(gene, weight)| gene.weight() == weight));
+
+    /* Many weight mutations and nothing structural */
+    config.independent_mutations = Some(IndependentMutations { split_connection: 0.0, create_connection: 0.0, toggle_weight: 0.0, weight_mutations: 100.0, ..Default::default() });
+    let mut net = NeatNetwork::new(3, 2, Arc::default(), Arc::default(), NetworkActivations::default(), Arc::new(config));
+    let weights: Vec<f32> = net.get_genes().iter().map(|gene| gene.weight()).collect();
+    net.mutate();
+    assert!(net.get_genes().len() == 8);
+    assert!(net.get_genes().iter().zip(weights).filter(|(gene, weight)| gene.weight() != *weight).count() > 1);
+}
+
+#[test]
+fn independent_gaussian_weights() -> () {
+    let mut config = NetworkConfig::default();
+    config.independent_mutations = Some(IndependentMutations { split_connection: 0.0, create_connection: 0.0, toggle_weight: 0.0, weight_mutations: 100.0, ..Default::default() });
+    config.gaussian_weights = Some(GaussianWeights { perturb_probability: 1.0, replace_probability: 0.0, ..Default::default() });
+    let mut net = NeatNetwork::new(3, 2, Arc::default(), Arc::default(), NetworkActivations::default(), Arc::new(config));
+
+    /* One pass over the genome, not one per weight mutation */
+    let record = net.mutate();
+    let changed = record.changes().iter().filter(|change| matches!(change, Change::Weight { .. })).count();
+    assert!(changed == net.get_genes().len());
+}
@@ -0,0 +1,62 @@
+use neat_algorithm::neural_network::{activation::{Activation, NetworkActivations}, builder::NetworkBuilder, connection_gene::ConnectionGene, network::NeatNetwork, plasticity::{HebbianRule, PLASTIC_DELTA_LIMIT}};
+
+fn linear_network() -> NeatNetwork {
+    let mut builder = NetworkBuilder::new(NetworkActivations::new(Activation::Linear, Activation::Linear));
+    let (x, out) = (builder.add_input("x"), builder.add_output("out"));
+    builder.set_bias(out, 0.).connect(x, out, 1.);
+    builder.build().unwrap()
+}
+
+#[test]
+fn rule() -> () {
+    let rule = HebbianRule::new(0.5, 1., 2., 3., 4.);
+    /* 0.5 * (1 * 2 * 3 + 2 * 2 + 3 * 3 + 4) */
+    assert!(rule.delta(2., 3.) == 11.5);
+    assert!(HebbianRule::default().delta(2., 3.) == 0.1 * 6.);
+}
+
+#[test]
+fn learns_within_episode() -> () {
+    let mut net = linear_network();
+    assert!(!net.is_plastic());
+    net.set_plasticity(0, Some(HebbianRule::new(0.5, 0., 0., 0., 1.)));
+    assert!(net.is_plastic());
+
+    assert!(net.calculate_output(vec![1.]) == vec![1.]);
+    assert!(net.calculate_output(vec![1.]) == vec![1.5]);
+    assert!(net.calculate_output(vec![2.]) == vec![4.]);
+    assert!(net.get_genes()[0].weight() == 1.);
+
+    net.reset_plasticity();
+    assert!(net.calculate_output(vec![1.]) == vec![1.]);
+}
+
+#[test]
+fn limited_drift() -> () {
+    let mut conn = ConnectionGene::new(0, 1, 0.5, 0);
+    conn.learn(1., 1.);
+    assert!(conn.plastic_delta() == 0.);
+
+    conn.set_plasticity(Some(HebbianRule::new(1., 0., 0., 0., 3.)));
+    conn.learn(1., 1.);
+    conn.learn(1., 1.);
+    assert!(conn.plastic_delta() == PLASTIC_DELTA_LIMIT);
+    assert!(conn.effective_weight() == 0.5 + PLASTIC_DELTA_LIMIT);
+}
+
+#[test]
+fn mutate() -> () {
+    let mut conn = ConnectionGene::new(0, 1, 0.5, 0);
+    conn.mutate_plasticity(0.1);
+    assert!(conn.plasticity() == Some(HebbianRule::default()));
+
+    conn.mutate_plasticity(0.1);
+    let rule = conn.plasticity().unwrap();
+    let default = HebbianRule::default();
+    let changes = [
+        rule.learning_rate - default.learning_rate, rule.a - default.a,
+        rule.b - default.b, rule.c - default.c, rule.d - default.d
+    ];
+    assert!(changes.iter().filter(|c| **c != 0.).count() <= 1);
+    assert!(changes.iter().all(|c| c.abs() < 0.1));
+}
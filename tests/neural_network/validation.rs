@@ -0,0 +1,54 @@
+use std::sync::Arc;
+use neat_algorithm::{neural_network::{activation::{Activation, NetworkActivations}, builder::NetworkBuilder, connection_gene::ConnectionGene, network::NeatNetwork, validation::Violation}, trainer::config::{ctrnn::CtrnnConfig, mutation::GenomeMutationProbablities, network_config::NetworkConfig}};
+
+#[test]
+fn valid_after_mutations() -> () {
+    let activations = NetworkActivations::new(Activation::LeakyRelu, Activation::Sigmoid);
+    let config = NetworkConfig { validate_after_mutation: true, ..Default::default() };
+    let mut net = NeatNetwork::new(3, 2, Arc::default(), Arc::default(), activations, Arc::new(config));
+    assert!(net.validate().is_ok());
+
+    /* Panics on the first broken invariant */
+    for _ in 0..500 { net.mutate(); }
+    net.calculate_output(vec![0.0, 1.0, 0.5]);
+    assert!(net.validate().is_ok());
+}
+
+#[test]
+fn broken_genes() -> () {
+    let activations = NetworkActivations::new(Activation::LeakyRelu, Activation::Sigmoid);
+    let genes = vec![
+        ConnectionGene::new(0, 1, 1., 0), ConnectionGene::new(0, 1, 1., 1),
+        ConnectionGene::new(1, 0, 1., 2),
+    ];
+    let net = NeatNetwork::new_with_genes(1, 1, Arc::default(), Arc::default(), activations, genes, Arc::default());
+    let violations = net.validate().unwrap_err();
+
+    assert!(violations.contains(&Violation::DuplicateConnection { node_in: 0, node_out: 1 }));
+    assert!(violations.contains(&Violation::ConnectionIntoInput { connection: 2 }));
+    assert!(violations.contains(&Violation::ConnectionFromOutput { connection: 2 }));
+    assert!(violations.contains(&Violation::Cycle));
+}
+
+#[test]
+fn recurrent() -> () {
+    let config = NetworkConfig {
+        mutation_probabilities: GenomeMutationProbablities { split_connection: 1, create_connection: 3, change_weight: 0, toggle_weight: 0, delete_connection: 0, delete_node: 0, nothing: 0 },
+        validate_after_mutation: true,
+        ctrnn: Some(CtrnnConfig { recurrent: true, mutation_probability: 0, ..Default::default() }),
+        ..Default::default()
+    };
+
+    /* Cycles are allowed, self loops included */
+    let mut builder = NetworkBuilder::new(NetworkActivations::default());
+    builder.with_network_config(Arc::new(config));
+    let (x, out) = (builder.add_input("x"), builder.add_output("out"));
+    let (h1, h2) = (builder.add_hidden(Activation::Relu, 0.), builder.add_hidden(Activation::Relu, 0.));
+    builder.connect(x, h1, 1.).connect(h1, h2, 1.).connect(h2, h1, 1.).connect(h2, h2, 0.5).connect(h2, out, 1.);
+    let mut net = builder.build().unwrap();
+
+    /* Panics on the first broken invariant */
+    for _ in 0..200 { net.mutate(); }
+    net.calculate_output(vec![1.0]);
+    assert!(net.validate().is_ok());
+}
@@ -0,0 +1,110 @@
+use neat_algorithm::{neural_network::network::NeatNetwork, trainer::{archipelago::Archipelago, config::migration::{MigrationConfig, MigrationTopology}, evolution::Evolution}};
+
+/// Bigger networks are fitter
+fn size(network: &mut NeatNetwork) -> f32 {
+    network.get_genes().len() as f32
+}
+
+fn archipelago(topology: MigrationTopology, interval: usize) -> Archipelago<fn(&mut NeatNetwork) -> f32> {
+    let mut archipelago = Archipelago::new(MigrationConfig { topology, interval, migrants: 2 });
+    for species_size in [3, 4, 5] {
+        archipelago.add_island(Evolution::new()
+            .batch_size(2)
+            .with_species_size(species_size)
+            .with_input_nodes(2)
+            .with_output_nodes(1)
+            .set_fitness_evaluator(size as fn(&mut NeatNetwork) -> f32));
+    }
+    archipelago
+}
+
+fn genes(network: &NeatNetwork) -> Vec<usize> {
+    network.get_genes().iter().map(|gene| gene.innovation_number()).collect()
+}
+
+#[test]
+fn shared_innovations() -> () {
+    let mut archipelago = archipelago(MigrationTopology::Ring, 0);
+    for _ in 0..5 { archipelago.generation(); }
+
+    let innovation = archipelago.islands()[0].species()[0].networks()[0].get_global_innovation();
+    assert!(archipelago.islands().iter().all(|island| island.species()[0].networks()[0].get_global_innovation() == innovation));
+    assert!(archipelago.get_generation() == 5 && archipelago.islands().iter().all(|island| island.get_generation() == 5));
+}
+
+#[test]
+fn migration() -> () {
+    for topology in [MigrationTopology::Ring, MigrationTopology::FullyConnected, MigrationTopology::Random] {
+        let mut archipelago = archipelago(topology, 0);
+        for _ in 0..3 { archipelago.generation(); }
+
+        let best: Vec<Vec<usize>> = archipelago.islands().iter().map(|island| {
+            let networks = island.species().iter().flat_map(|s| s.networks().iter());
+            genes(networks.max_by(|a, b| a.previous_average_fitness().total_cmp(&b.previous_average_fitness())).unwrap())
+        }).collect();
+        archipelago.migrate();
+
+        /* The best of the previous island arrived */
+        let contains = |island: usize, genes_of: &Vec<usize>| archipelago.islands()[island].species().iter()
+            .any(|s| s.networks().iter().any(|n| &genes(n) == genes_of));
+        match topology {
+            MigrationTopology::Ring => assert!((0..3).all(|i| contains((i + 1) % 3, &best[i]))),
+            MigrationTopology::FullyConnected => assert!((0..3).all(|i| (0..3).all(|j| contains(j, &best[i])))),
+            MigrationTopology::Random => assert!((0..3).all(|i| (0..3).filter(|&j| j != i).any(|j| contains(j, &best[i])))),
+        }
+
+        let best_network = archipelago.best_network().unwrap().previous_average_fitness();
+        assert!(archipelago.islands().iter().all(|island| island.species().iter().all(|s| s.networks().iter().all(|n| n.previous_average_fitness() <= best_network))));
+    }
+}
+
+#[test]
+fn late_island_innovations() -> () {
+    let mut archipelago = archipelago(MigrationTopology::Ring, 2);
+    for _ in 0..5 { archipelago.generation(); }
+
+    /* Creating its networks must not rewind the shared counter */
+    archipelago.add_island(Evolution::new()
+        .batch_size(2)
+        .with_species_size(3)
+        .with_input_nodes(2)
+        .with_output_nodes(1)
+        .set_fitness_evaluator(size as fn(&mut NeatNetwork) -> f32));
+    for _ in 0..5 { archipelago.generation(); }
+
+    let networks = archipelago.islands().iter().flat_map(|island| island.species().iter().flat_map(|s| s.networks().iter()));
+    assert!(super::innovations_consistent(networks));
+}
+
+#[test]
+fn migrants_not_overwritten() -> () {
+    let mut archipelago = archipelago(MigrationTopology::FullyConnected, 0);
+    for _ in 0..3 { archipelago.generation(); }
+
+    let weights = |network: &NeatNetwork| -> Vec<f32> { network.get_genes().iter().map(|gene| gene.weight()).collect() };
+    let populations: Vec<Vec<Vec<f32>>> = archipelago.islands().iter()
+        .map(|island| island.species().iter().flat_map(|s| s.networks().iter()).map(weights).collect())
+        .collect();
+    archipelago.migrate();
+
+    /* Two migrants from each of the two other islands */
+    for (index, island) in archipelago.islands().iter().enumerate() {
+        let foreign = island.species().iter().flat_map(|s| s.networks().iter())
+            .filter(|network| (0..3).filter(|&other| other != index).any(|other| populations[other].contains(&weights(network))))
+            .count();
+        assert!(foreign == 4);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Island 1 has 3 inputs and 1 outputs")]
+fn mismatched_island() -> () {
+    let mut archipelago = Archipelago::new(MigrationConfig::default());
+    for inputs in [2, 3] {
+        archipelago.add_island(Evolution::new()
+            .batch_size(2)
+            .with_input_nodes(inputs)
+            .with_output_nodes(1)
+            .set_fitness_evaluator(size as fn(&mut NeatNetwork) -> f32));
+    }
+}
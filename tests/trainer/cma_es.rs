@@ -0,0 +1,49 @@
+use neat_algorithm::{neural_network::{activation::{Activation, NetworkActivations}, builder::{BuilderNode, NetworkBuilder}, network::NeatNetwork}, trainer::{cma_es::{polish, CmaEs}, config::cma_es::CmaEsConfig}};
+
+fn linear_network() -> NeatNetwork {
+    let mut builder = NetworkBuilder::new(NetworkActivations::new(Activation::Linear, Activation::Linear));
+    let (x, out) = (builder.add_input("x"), builder.add_output("out"));
+    builder.set_bias(out, 0.).connect(x, out, 0.).connect_disabled(BuilderNode::Bias, out, 5.);
+    builder.build().unwrap()
+}
+
+#[test]
+fn parameter_vector() -> () {
+    let mut net = linear_network();
+    /* Enabled weight, then the output bias */
+    assert!(net.parameter_vector() == vec![0., 0.]);
+    net.set_parameter_vector(&[2., 1.]);
+    assert!(net.get_genes()[0].weight() == 2. && net.get_genes()[1].weight() == 5.);
+    assert!(net.node_genes()[1].bias() == 1. && net.node_genes()[2].bias() == 1.);
+    assert!(net.parameter_vector() == vec![2., 1.]);
+}
+
+#[test]
+fn sphere() -> () {
+    let mut cma = CmaEs::new(vec![0.; 10], 1., None);
+    for _ in 0..300 {
+        let samples = cma.ask();
+        let fitnesses: Vec<f32> = samples.iter().map(|s| -s.iter().map(|x| (x - 3.).powi(2)).sum::<f32>()).collect();
+        cma.tell(&samples, &fitnesses);
+    }
+    assert!(cma.mean().iter().all(|x| (x - 3.).abs() < 0.05), "{:?}", cma.mean());
+    assert!(cma.step_size() < 0.1);
+}
+
+#[test]
+fn polish_weights() -> () {
+    let mut evaluator = |net: &mut NeatNetwork| {
+        let error: f32 = (0..10).map(|i| i as f32 / 10.)
+            .map(|x| (net.calculate_output(vec![x])[0] - (2. * x + 1.)).powi(2))
+            .sum();
+        100. - error
+    };
+    let config = CmaEsConfig { generations: 150, initial_step_size: 0.5, ..Default::default() };
+
+    let mut net = linear_network();
+    let before = evaluator(&mut net);
+    let after = polish(&mut net, &mut evaluator, &config);
+    assert!(after > before && after > 99.9);
+    assert!(evaluator(&mut net) == after);
+    assert!((net.get_genes()[0].weight() - 2.).abs() < 0.1);
+}
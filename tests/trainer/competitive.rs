@@ -0,0 +1,95 @@
+use std::sync::Arc;
+use neat_algorithm::{games::tic_tac_toe::{Cell, Outcome, TicTacToe, TicTacToeEvaluator}, neural_network::{activation::NetworkActivations, connection_gene::ConnectionGene, network::NeatNetwork}, trainer::{competitive::{CompetitiveEvaluator, PairwiseEvaluator}, config::competitive::{Aggregation, CompetitiveConfig, Matchmaking}, evolution::Evolution, fitness::FitnessEvaluator}};
+
+#[test]
+fn tic_tac_toe() -> () {
+    let mut game = TicTacToe::new();
+    for cell in [0, 3, 1, 4] { assert!(game.play(cell)); }
+    assert!(!game.play(0));
+    assert!(game.outcome().is_none());
+    assert!(game.input(Cell::X) == vec![1., 1., 0., -1., -1., 0., 0., 0., 0.]);
+
+    assert!(game.play(2));
+    assert!(game.outcome() == Some(Outcome::Winner(Cell::X)));
+    assert!(!game.play(5));
+}
+
+#[test]
+fn nan_outputs() -> () {
+    /* The first cell's output is NaN */
+    let genes = vec![ConnectionGene::new(0, 9, f32::NAN, 0)];
+    let mut nan = NeatNetwork::new_with_genes(9, 9, Arc::default(), Arc::default(), NetworkActivations::default(), genes, Arc::default());
+    assert!(nan.calculate_output(vec![0.; 9])[0].is_nan());
+
+    /* Every other cell ties, so cells are played in order with the
+    NaN cell last, and O completes the 2-4-6 diagonal */
+    let mut other = nan.clone();
+    assert!(TicTacToe::play_game(&mut nan, &mut other) == Outcome::Winner(Cell::O));
+}
+
+/// The side with more connection genes wins
+#[derive(Clone)]
+struct Bigger;
+impl PairwiseEvaluator for Bigger {
+    fn run(&mut self, a: &mut NeatNetwork, b: &mut NeatNetwork) -> (f32, f32) {
+        let (a, b) = (a.get_genes().len(), b.get_genes().len());
+        (if a > b { 1. } else { 0. }, if b > a { 1. } else { 0. })
+    }
+}
+
+fn evolution<P: PairwiseEvaluator + Send + Sync>(evaluator: P, config: CompetitiveConfig, inputs: usize, outputs: usize) -> Evolution<CompetitiveEvaluator<P>> {
+    Evolution::new()
+        .batch_size(3)
+        .with_species_size(4)
+        .with_input_nodes(inputs)
+        .with_output_nodes(outputs)
+        .set_fitness_evaluator(CompetitiveEvaluator::new(evaluator, config))
+        .build()
+}
+
+#[test]
+fn matchmaking() -> () {
+    for matchmaking in [Matchmaking::RoundRobin(4), Matchmaking::HallOfFame(2), Matchmaking::SharedSampling(3)] {
+        let config = CompetitiveConfig { matchmaking, aggregation: Aggregation::WinRate, hall_of_fame_size: 2 };
+        let mut evolution = evolution(Bigger, config, 2, 1);
+        for _ in 0..4 { evolution.generation(); }
+
+        let evaluator = evolution.get_fitness_evaluator();
+        let evaluator = evaluator.lock().unwrap();
+        assert!(evaluator.hall_of_fame().len() == 2);
+        let (Matchmaking::RoundRobin(n) | Matchmaking::HallOfFame(n) | Matchmaking::SharedSampling(n)) = matchmaking;
+        assert!(evaluator.opponents().len() == n);
+        assert!(evolution.species().iter().all(|s| s.networks().iter().all(|n| (0. ..=1.).contains(&n.previous_fitness()))));
+    }
+}
+
+/// Replays fixed match results, in order
+#[derive(Clone)]
+struct Scripted(Vec<(f32, f32)>, usize);
+impl PairwiseEvaluator for Scripted {
+    fn run(&mut self, _: &mut NeatNetwork, _: &mut NeatNetwork) -> (f32, f32) {
+        self.1 += 1;
+        self.0[(self.1 - 1) % self.0.len()]
+    }
+}
+
+#[test]
+fn aggregation() -> () {
+    let mut network = NeatNetwork::new(2, 1, Arc::default(), Arc::default(), NetworkActivations::default(), Arc::default());
+    let population = vec![network.clone(), network.clone(), network.clone()];
+    let population: Vec<&NeatNetwork> = population.iter().collect();
+    let results = vec![(1., 0.), (0.5, 0.5), (0., 2.)];
+
+    for (aggregation, expected) in [(Aggregation::Mean, 0.5), (Aggregation::Min, 0.), (Aggregation::WinRate, 1. / 3.)] {
+        let config = CompetitiveConfig { matchmaking: Matchmaking::RoundRobin(3), aggregation, ..Default::default() };
+        let mut competitive = CompetitiveEvaluator::new(Scripted(results.clone(), 0), config);
+        competitive.prepare_generation(&population);
+        assert!(competitive.run(&mut network) == expected);
+    }
+
+    /* Two games of tic-tac-toe, every game hands out 1 point */
+    let mut x = NeatNetwork::new(9, 9, Arc::default(), Arc::default(), NetworkActivations::default(), Arc::default());
+    let mut o = x.clone();
+    let (a, b) = TicTacToeEvaluator.run(&mut x, &mut o);
+    assert!(a + b == 2.);
+}
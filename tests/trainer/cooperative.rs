@@ -0,0 +1,71 @@
+use std::sync::Arc;
+use neat_algorithm::{neural_network::{activation::NetworkActivations, network::NeatNetwork}, trainer::{config::cooperative::{CooperativeConfig, CreditAssignment}, cooperative::{CooperativeEvolution, TeamEvaluator, TeamMember}, evolution::Evolution, fitness::FitnessEvaluator}};
+
+/// A preprocessing network feeding a controller, which
+/// should output 0.25 for the input 1.0
+#[derive(Clone)]
+struct Pipeline;
+impl TeamEvaluator for Pipeline {
+    fn run(&mut self, team: &mut [NeatNetwork]) -> f32 {
+        assert!(team.len() == 2 && team[0].input_size() == 1 && team[1].input_size() == 2);
+        let features = team[0].calculate_output(vec![1.]);
+        1. - (team[1].calculate_output(features)[0] - 0.25).abs()
+    }
+}
+
+/// Team fitness is the amount of connection genes of the
+/// second member, so it only depends on the partner
+#[derive(Clone)]
+struct PartnerSize;
+impl TeamEvaluator for PartnerSize {
+    fn run(&mut self, team: &mut [NeatNetwork]) -> f32 {
+        team[1].get_genes().len() as f32
+    }
+}
+
+#[test]
+fn credit_assignment() -> () {
+    let network = |inputs: usize| NeatNetwork::new(inputs, 1, Arc::default(), Arc::default(), NetworkActivations::default(), Arc::default());
+    let (mut small, mut large) = (network(1), network(3));
+    small.mutate();
+    let teams = vec![vec![small.clone()], vec![large.clone()]];
+
+    let mut best = TeamMember::new(PartnerSize, CreditAssignment::BestPartner, 0);
+    best.set_partner_teams(teams.clone());
+    let mut average = TeamMember::new(PartnerSize, CreditAssignment::AverageOverPartners, 0);
+    average.set_partner_teams(teams);
+
+    let sizes = (small.get_genes().len() as f32, large.get_genes().len() as f32);
+    assert!(best.run(&mut large) == sizes.0.max(sizes.1));
+    assert!(average.run(&mut large) == (sizes.0 + sizes.1) / 2.);
+    assert!(TeamMember::new(PartnerSize, CreditAssignment::BestPartner, 0).run(&mut large) == 0.);
+}
+
+#[test]
+fn populations() -> () {
+    let config = CooperativeConfig { credit_assignment: CreditAssignment::AverageOverPartners, partners: 2 };
+    let mut coop = CooperativeEvolution::new(Pipeline, config);
+    coop.add_population(Evolution::new().batch_size(2).with_species_size(3).with_input_nodes(1).with_output_nodes(2))
+        .add_population(Evolution::new().batch_size(2).with_species_size(3).with_input_nodes(2).with_output_nodes(1));
+
+    for _ in 0..3 { coop.generation(); }
+    for (index, population) in coop.populations().iter().enumerate() {
+        let evaluator = population.get_fitness_evaluator();
+        let evaluator = evaluator.lock().unwrap();
+        assert!(evaluator.population() == index);
+        assert!(evaluator.partner_teams().len() == 2 && evaluator.partner_teams().iter().all(|team| team.len() == 1));
+        assert!(population.get_generation() == 3);
+    }
+
+    let mut team = coop.best_team();
+    assert!(team[0].input_size() == 1 && team[1].input_size() == 2);
+    assert!((0. ..=1.).contains(&Pipeline.run(&mut team)));
+}
+
+#[test]
+#[should_panic(expected = "Population 1 has no networks")]
+fn empty_population() -> () {
+    let mut coop = CooperativeEvolution::new(Pipeline, CooperativeConfig::default());
+    coop.add_population(Evolution::new().batch_size(2).with_species_size(3).with_input_nodes(1).with_output_nodes(2))
+        .add_population(Evolution::new().batch_size(0).with_input_nodes(2).with_output_nodes(1));
+}
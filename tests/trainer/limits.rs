@@ -0,0 +1,98 @@
+use std::sync::Arc;
+use neat_algorithm::{neural_network::{activation::NetworkActivations, network::NeatNetwork}, trainer::{config::{ctrnn::CtrnnConfig, limits::ComplexityLimits, multi_objective::Complexity, mutation::GenomeMutationProbablities, network_config::NetworkConfig, parsimony::ParsimonyConfig}, species::Species}};
+
+fn structural(split_connection: usize, create_connection: usize, toggle_weight: usize) -> GenomeMutationProbablities {
+    GenomeMutationProbablities { split_connection, create_connection, toggle_weight, change_weight: 0, delete_connection: 0, delete_node: 0, nothing: 0 }
+}
+
+fn network(limits: ComplexityLimits) -> NeatNetwork {
+    let mut config = NetworkConfig::default();
+    config.mutation_probabilities = structural(40, 40, 20);
+    config.validate_after_mutation = true;
+    config.limits = limits;
+    NeatNetwork::new(3, 2, Arc::default(), Arc::default(), NetworkActivations::default(), Arc::new(config))
+}
+
+#[test]
+fn depth() -> () {
+    let mut net = network(ComplexityLimits::default());
+    assert!(net.depth() == 1);
+
+    let mut config = (*net.network_config()).clone();
+    config.mutation_probabilities = structural(1, 0, 0);
+    net.set_network_config(Arc::new(config));
+    net.mutate();
+    assert!(net.depth() == 2);
+}
+
+#[test]
+fn limits_respected() -> () {
+    let limits = ComplexityLimits { max_hidden_nodes: Some(3), max_connections: Some(14), max_depth: Some(3) };
+    let mut net = network(limits);
+    for _ in 0..1000 {
+        net.mutate();
+        assert!(Complexity::HiddenNodes.of(&net) <= 3);
+        assert!(net.get_genes().len() <= 14);
+        assert!(net.depth() <= 3);
+    }
+    assert!(Complexity::HiddenNodes.of(&net) > 0);
+}
+
+#[test]
+fn unlimited() -> () {
+    let mut net = network(ComplexityLimits::default());
+    for _ in 0..300 { net.mutate(); }
+    assert!(net.get_genes().len() > 14);
+}
+
+#[test]
+fn parsimony() -> () {
+    let simple = network(ComplexityLimits::default());
+    let mut complex = simple.clone();
+    for _ in 0..20 { complex.mutate(); }
+    assert!(Complexity::Both.of(&complex) > Complexity::Both.of(&simple));
+
+    let mut species = Species::new(Arc::default(), Arc::default(), simple, 1, 0, false);
+    let mut simple = species.networks()[0].clone();
+    simple.record_fitness(1.0);
+    complex.record_fitness(1.0);
+    assert!(species.selection_score(&simple) == species.selection_score(&complex));
+
+    species.set_parsimony(Some(ParsimonyConfig { complexity: Complexity::Both, coefficient: 0.1 }));
+    assert!(species.selection_score(&simple) > species.selection_score(&complex));
+    let difference = species.selection_score(&simple) - species.selection_score(&complex);
+    let expected = 0.1 * (Complexity::Both.of(&complex) - Complexity::Both.of(&simple)) as f32;
+    assert!((difference - expected).abs() < 1e-4);
+}
+
+#[test]
+fn connected_hidden_nodes() -> () {
+    let mut config = NetworkConfig::default();
+    config.mutation_probabilities = GenomeMutationProbablities { split_connection: 30, create_connection: 30, toggle_weight: 10, change_weight: 0, delete_connection: 10, delete_node: 10, nothing: 0 };
+    let mut net = NeatNetwork::new(3, 2, Arc::default(), Arc::default(), NetworkActivations::default(), Arc::new(config));
+
+    /* The kept count matches a full scan through mutations and reverts */
+    let hidden_start = 3 + 2 + 1;
+    for i in 0..500 {
+        let record = net.mutate();
+        if i % 3 == 0 { net.revert(record); }
+        let scanned = (hidden_start..net.node_genes().len())
+            .filter(|&node| net.get_genes().iter().any(|c| c.node_in() == node || c.node_out() == node))
+            .count();
+        assert!(net.connected_hidden_nodes() == scanned);
+    }
+}
+
+#[test]
+fn recurrent_depth() -> () {
+    let mut config = NetworkConfig::default();
+    config.mutation_probabilities = structural(40, 40, 0);
+    config.limits = ComplexityLimits { max_depth: Some(3), ..Default::default() };
+    config.ctrnn = Some(CtrnnConfig { recurrent: true, mutation_probability: 0, ..Default::default() });
+    let mut net = NeatNetwork::new(3, 2, Arc::default(), Arc::default(), NetworkActivations::default(), Arc::new(config));
+
+    /* Depth isn't defined with cycles, so it doesn't stop growth */
+    for _ in 0..300 { net.mutate(); }
+    assert!(NeatNetwork::has_cycle(net.local_occupied_connections().iter()));
+    assert!(net.get_genes().len() > 14);
+}
@@ -0,0 +1,111 @@
+use std::sync::{Arc, Mutex};
+use neat_algorithm::{neural_network::{activation::{Activation, NetworkActivations}, builder::NetworkBuilder, network::NeatNetwork}, trainer::{config::{map_elites::{BehaviourDimension, MapElitesConfig}, mutation::GenomeMutationProbablities}, fitness::{Evaluation, FitnessEvaluator}, map_elites::MapElites}};
+
+/// Behaviour is the output for a fixed input,
+/// fitness prefers outputs close to 0.5
+#[derive(Clone)]
+struct OutputBehaviour;
+impl FitnessEvaluator for OutputBehaviour {
+    fn run(&mut self, network: &mut NeatNetwork) -> f32 { self.evaluate(network).fitness }
+    fn evaluate(&mut self, network: &mut NeatNetwork) -> Evaluation {
+        let output = network.calculate_output(vec![1., -1.]);
+        let fitness = 2. - output.iter().map(|o| (o - 0.5).abs()).sum::<f32>();
+        Evaluation::new(fitness, output)
+    }
+}
+
+fn config(crossover_probability: f64) -> MapElitesConfig {
+    let mut config = MapElitesConfig::new(vec![BehaviourDimension::new(0., 1., 4); 2]);
+    config.batch_size = 20;
+    config.crossover_probability = crossover_probability;
+    config
+}
+
+#[test]
+fn bins() -> () {
+    let dimension = BehaviourDimension::new(-1., 1., 4);
+    assert!(dimension.bin(-1.) == 0);
+    assert!(dimension.bin(0.) == 2);
+    assert!(dimension.bin(0.99) == 3);
+
+    /* Outside of the range lands in the outer cells */
+    assert!(dimension.bin(-5.) == 0);
+    assert!(dimension.bin(1.) == 3);
+    assert!(dimension.bin(f32::NAN) == 0);
+}
+
+#[test]
+fn elites_only_improve() -> () {
+    let mut map = MapElites::new(2, 2, OutputBehaviour, config(0.5));
+    assert!(map.cells() == 16 && map.coverage() == 0.);
+
+    let mut qd_score = 0.;
+    for _ in 0..10 {
+        map.generation();
+        assert!(map.qd_score() >= qd_score);
+        qd_score = map.qd_score();
+    }
+
+    assert!(map.get_generation() == 10);
+    assert!(map.coverage() > 0. && map.coverage() <= 1.);
+    for (cell, elite) in map.elites() {
+        assert!(&map.cell(&elite.behaviour) == cell);
+        assert!(elite.network.previous_fitness() == elite.fitness);
+    }
+
+    let best = map.best().unwrap().fitness;
+    assert!(map.elites().values().all(|elite| elite.fitness <= best));
+}
+
+#[test]
+fn save() -> () {
+    let mut map = MapElites::new(2, 2, OutputBehaviour, config(0.));
+    map.generation();
+
+    let directory = std::env::temp_dir().join("neat_map_elites_test");
+    let directory = directory.to_string_lossy();
+    map.save(&directory);
+
+    for (cell, elite) in map.elites() {
+        let name = cell.iter().map(|bin| bin.to_string()).collect::<Vec<String>>().join("_");
+        let mut retrieved = NeatNetwork::retrieve(&format!("{directory}/{name}.bin")).unwrap();
+        let mut network = elite.network.clone();
+        assert!(retrieved.calculate_output(vec![1., -1.]) == network.calculate_output(vec![1., -1.]));
+    }
+    std::fs::remove_dir_all(&*directory).unwrap();
+}
+
+#[test]
+fn innovations() -> () {
+    let mut config = config(0.);
+    config.network_config.mutation_probabilities = GenomeMutationProbablities { split_connection: 1, create_connection: 0, change_weight: 0, toggle_weight: 0, delete_connection: 0, delete_node: 0, nothing: 0 };
+    let mut map = MapElites::new(2, 2, OutputBehaviour, config);
+
+    /* Every child of the first generation is a new network */
+    map.generation();
+    assert!(super::innovations_consistent(map.elites().values().map(|elite| &elite.network)));
+}
+
+#[test]
+fn crossover_keeps_nodes() -> () {
+    let mut config = config(1.);
+    config.network_config.mutation_probabilities = GenomeMutationProbablities { split_connection: 0, create_connection: 0, change_weight: 0, toggle_weight: 0, delete_connection: 0, delete_node: 0, nothing: 1 };
+    let mut map = MapElites::new(2, 2, OutputBehaviour, config);
+
+    let mut builder = NetworkBuilder::new(NetworkActivations::default());
+    let (x, y) = (builder.add_input("x"), builder.add_input("y"));
+    let (a, b) = (builder.add_output("a"), builder.add_output("b"));
+    let hidden = builder.add_hidden(Activation::Sigmoid, 0.7);
+    builder.set_bias(a, -2.).connect(x, hidden, 1.).connect(hidden, a, 1.).connect(y, b, 1.);
+    let mut elite = builder.build().unwrap();
+    elite.set_time_constant(5, 3.);
+    elite.evaluate_fitness(Arc::new(Mutex::new(OutputBehaviour)));
+    assert!(map.insert(elite));
+
+    /* Every child is a crossover of the elite with itself */
+    map.generation();
+    assert!(map.elites().values().all(|elite| {
+        let nodes = elite.network.node_genes();
+        nodes[2].bias() == -2. && nodes[5].bias() == 0.7 && nodes[5].time_constant() == 3. && nodes[5].activation_function() == Some(Activation::Sigmoid)
+    }));
+}
@@ -0,0 +1,23 @@
+use std::collections::HashMap;
+use neat_algorithm::neural_network::network::NeatNetwork;
+
+pub mod cma_es;
+pub mod novelty;
+pub mod map_elites;
+pub mod nsga2;
+pub mod competitive;
+pub mod cooperative;
+pub mod archipelago;
+pub mod real_time;
+pub mod phased_search;
+pub mod limits;
+pub mod self_adaptation;
+
+/// True if no innovation number is used by two
+/// different (node_in, node_out) pairs
+pub fn innovations_consistent<'a>(networks: impl Iterator<Item = &'a NeatNetwork>) -> bool {
+    let mut pairs: HashMap<usize, (usize, usize)> = HashMap::new();
+    networks.flat_map(|network| network.get_genes().iter()).all(|gene| {
+        *pairs.entry(gene.innovation_number()).or_insert((gene.node_in(), gene.node_out())) == (gene.node_in(), gene.node_out())
+    })
+}
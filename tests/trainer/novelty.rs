@@ -0,0 +1,59 @@
+use neat_algorithm::{neural_network::network::NeatNetwork, trainer::{config::novelty::{ArchiveInsertion, NoveltyConfig}, evolution::Evolution, fitness::{Evaluation, FitnessEvaluator}, novelty::NoveltyArchive}};
+
+#[test]
+fn k_nearest() -> () {
+    let archive = NoveltyArchive::new(2, ArchiveInsertion::Threshold(0.));
+    let (a, b, c) = (vec![1., 0.], vec![3., 0.], vec![0., 4.]);
+
+    /* Nearest two of 1, 3 and 4 */
+    assert!(archive.novelty(&[0., 0.], &[&a, &b, &c]) == 2.);
+    assert!(archive.novelty(&[0., 0.], &[]) == 0.);
+}
+
+#[test]
+fn insertion() -> () {
+    let behaviours = vec![vec![0.], vec![1.], vec![10.], Vec::new()];
+
+    let mut most_novel = NoveltyArchive::new(1, ArchiveInsertion::MostNovel(1));
+    let scores = most_novel.score_generation(&behaviours);
+    assert!(scores == vec![1., 1., 9., 0.]);
+    assert!(most_novel.behaviours() == &vec![vec![10.]]);
+
+    /* The archive counts when scoring the next generation */
+    assert!(most_novel.score_generation(&[vec![9.]]) == vec![1.]);
+
+    let mut threshold = NoveltyArchive::new(1, ArchiveInsertion::Threshold(1.));
+    threshold.score_generation(&behaviours);
+    assert!(threshold.len() == 3);
+
+    let mut never = NoveltyArchive::new(1, ArchiveInsertion::Random(0.));
+    never.score_generation(&behaviours);
+    assert!(never.is_empty());
+}
+
+/// Constant fitness, behaviour is the output for a fixed input
+#[derive(Clone)]
+struct OutputBehaviour;
+impl FitnessEvaluator for OutputBehaviour {
+    fn run(&mut self, _: &mut NeatNetwork) -> f32 { 1. }
+    fn evaluate(&mut self, network: &mut NeatNetwork) -> Evaluation {
+        Evaluation::new(1., network.calculate_output(vec![1., -1.]))
+    }
+}
+
+#[test]
+fn evolution() -> () {
+    let mut evolution = Evolution::new()
+        .batch_size(4)
+        .with_species_size(5)
+        .with_input_nodes(2)
+        .with_output_nodes(2)
+        .novelty_search(Some(NoveltyConfig { k: 3, insertion: ArchiveInsertion::MostNovel(2), novelty_weight: 1. }))
+        .set_fitness_evaluator(OutputBehaviour)
+        .build();
+
+    for _ in 0..3 { evolution.generation(); }
+    assert!(evolution.novelty_archive().unwrap().len() == 6);
+    assert!(evolution.species().iter().all(|s| s.networks().iter().all(|n| n.behaviour().len() == 2)));
+    assert!(evolution.species().iter().any(|s| s.networks().iter().any(|n| n.novelty() > 0.)));
+}
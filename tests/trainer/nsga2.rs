@@ -0,0 +1,69 @@
+use neat_algorithm::{neural_network::network::NeatNetwork, trainer::{config::multi_objective::{Complexity, MultiObjectiveConfig}, evolution::Evolution, fitness::{Evaluation, FitnessEvaluator}, nsga2}};
+
+#[test]
+fn dominates() -> () {
+    assert!(nsga2::dominates(&[1., 1.], &[1., 0.]));
+    assert!(!nsga2::dominates(&[1., 0.], &[0., 1.]));
+
+    /* Equal vectors don't dominate eachother */
+    assert!(!nsga2::dominates(&[1., 1.], &[1., 1.]));
+}
+
+#[test]
+fn fronts() -> () {
+    let objectives = vec![vec![0., 0.], vec![3., 0.], vec![2., 2.], vec![0., 3.], vec![1., 1.]];
+    let fronts = nsga2::non_dominated_sort(&objectives);
+    assert!(fronts == vec![vec![1, 2, 3], vec![4], vec![0]]);
+
+    /* Extremes are always kept, (2, 2) sits in between */
+    let distances = nsga2::crowding_distance(&objectives, &fronts[0]);
+    assert!(distances[0] == f32::INFINITY && distances[2] == f32::INFINITY);
+    assert!(distances[1] == 2.);
+
+    let ranks = nsga2::rank(&objectives);
+    assert!(ranks[0].0 == 2 && ranks[4].0 == 1 && ranks[2] == (0, 2.));
+}
+
+/// Two conflicting objectives, the first and negated second output
+#[derive(Clone)]
+struct Conflicting;
+impl FitnessEvaluator for Conflicting {
+    fn run(&mut self, network: &mut NeatNetwork) -> f32 { self.evaluate(network).fitness }
+    fn evaluate(&mut self, network: &mut NeatNetwork) -> Evaluation {
+        let output = network.calculate_output(vec![1., -1.]);
+        Evaluation::new(output[0], Vec::new()).with_objectives(vec![output[0], -output[1]])
+    }
+}
+
+#[test]
+fn evolution() -> () {
+    let config = MultiObjectiveConfig { minimize_complexity: Some(Complexity::Both) };
+    let mut evolution = Evolution::new()
+        .batch_size(3)
+        .with_species_size(5)
+        .with_input_nodes(2)
+        .with_output_nodes(2)
+        .multi_objective(Some(config))
+        .set_fitness_evaluator(Conflicting)
+        .build();
+
+    for _ in 0..3 { evolution.generation(); }
+    let front = evolution.pareto_front();
+    assert!(!front.is_empty());
+    assert!(front.iter().all(|n| n.objectives().len() == 2 && config.objectives(n).len() == 3));
+
+    /* Every network outside of the front is selected after it */
+    let population: Vec<&NeatNetwork> = evolution.species().iter().flat_map(|s| s.networks().iter()).collect();
+    let ranked: Vec<&&NeatNetwork> = population.iter().filter(|n| n.pareto_rank() > 0).collect();
+    assert!(ranked.iter().all(|n| n.pareto_score() < front[0].pareto_score()));
+
+    /* The front still holds the genomes which were ranked, none dominates another */
+    let objectives: Vec<Vec<f32>> = front.iter().map(|n| {
+        let mut n = n.clone();
+        let evaluation = Conflicting.evaluate(&mut n);
+        let mut objectives = evaluation.objectives;
+        objectives.push(-(Complexity::Both.of(&n) as f32));
+        objectives
+    }).collect();
+    assert!(nsga2::rank(&objectives).iter().all(|(rank, _)| *rank == 0));
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+use neat_algorithm::{neural_network::{activation::NetworkActivations, mutation_record::Change, network::NeatNetwork}, trainer::{config::{ctrnn::CtrnnConfig, multi_objective::Complexity, mutation::GenomeMutationProbablities, network_config::NetworkConfig, phased_search::PhasedSearchConfig, plasticity::PlasticityConfig}, evolution::Evolution, phased_search::{Phase, PhasedSearch}}};
+
+#[test]
+fn switches_phase() -> () {
+    let mut search = PhasedSearch::new(PhasedSearchConfig { complexity_jump: 5., stall_generations: 2, ..Default::default() });
+    assert!(search.update(10., 1.) == None);
+    assert!(search.update(15., 2.) == None);
+    assert!(search.update(16., 3.) == Some(Phase::Simplifying));
+
+    /* Back once complexity stops dropping */
+    assert!(search.update(12., 3.) == None);
+    assert!(search.update(12., 3.) == None);
+    assert!(search.update(13., 3.) == Some(Phase::Complexifying));
+
+    /* The floor is now 13 */
+    assert!(search.update(18., 3.) == None);
+    assert!(search.update(18.5, 3.) == Some(Phase::Simplifying));
+    assert!(search.phase() == Phase::Simplifying);
+    assert!(search.complexity_history() == &vec![10., 15., 16., 12., 12., 13., 18., 18.5]);
+}
+
+#[test]
+fn plateau() -> () {
+    let mut search = PhasedSearch::new(PhasedSearchConfig { complexity_jump: 100., plateau_generations: 3, ..Default::default() });
+    assert!(search.update(1., 5.) == None);
+    assert!(search.update(1., 5.) == None);
+    assert!(search.update(1., 5.) == None);
+    assert!(search.update(1., 5.) == Some(Phase::Simplifying));
+}
+
+#[test]
+fn delete_mutations() -> () {
+    let mut config = NetworkConfig::default();
+    config.mutation_probabilities.split_connection = 100;
+    config.validate_after_mutation = true;
+    let mut net = NeatNetwork::new(3, 2, Arc::default(), Arc::default(), NetworkActivations::default(), Arc::new(config.clone()));
+    for _ in 0..30 { net.mutate(); }
+    assert!(Complexity::HiddenNodes.of(&net) > 0);
+
+    /* Only delete mutations until nothing is left */
+    config.mutation_probabilities = PhasedSearchConfig::default().simplification_mutations;
+    net.set_network_config(Arc::new(config));
+    for _ in 0..500 {
+        net.mutate();
+        assert!(net.validate().is_ok());
+    }
+    assert!(net.get_genes().is_empty());
+    assert!(Complexity::Both.of(&net) == 0);
+    assert!(net.calculate_output(vec![1., 1., 1.]).len() == 2);
+}
+
+#[test]
+fn evolution() -> () {
+    let config = PhasedSearchConfig { complexity_jump: 0.5, stall_generations: 1, ..Default::default() };
+    let mut evolution = Evolution::new()
+        .batch_size(2)
+        .with_species_size(4)
+        .with_input_nodes(2)
+        .with_output_nodes(1)
+        .mutation_probabilities(GenomeMutationProbablities { split_connection: 50, create_connection: 50, ..Default::default() })
+        .plasticity(Some(PlasticityConfig::default()))
+        .ctrnn(Some(CtrnnConfig::default()))
+        .phased_search(Some(config))
+        .set_fitness_evaluator(1.)
+        .build();
+
+    let mut phases = Vec::new();
+    for _ in 0..30 {
+        evolution.generation();
+        let phase = evolution.phased_search().unwrap().phase();
+        let deleting = evolution.species()[0].networks()[0].network_config().mutation_probabilities.delete_connection > 0;
+        assert!(deleting == (phase == Phase::Simplifying));
+        phases.push(phase);
+
+        /* Learning rules and time constants don't change either */
+        if phase == Phase::Simplifying {
+            let mut network = evolution.species()[0].networks()[0].clone();
+            for _ in 0..20 {
+                assert!(network.mutate().changes().iter().all(|change| matches!(change, Change::ConnectionRemoved { .. })));
+            }
+        }
+    }
+
+    assert!(evolution.phased_search().unwrap().complexity_history().len() == 30);
+    assert!(phases.contains(&Phase::Simplifying));
+}
@@ -0,0 +1,65 @@
+use neat_algorithm::trainer::{config::real_time::RealTimeConfig, evolution::Evolution, real_time::{RealTimeEvolution, Replacement}};
+
+fn real_time(config: RealTimeConfig) -> RealTimeEvolution<f32> {
+    let evolution = Evolution::new()
+        .batch_size(3)
+        .with_species_size(4)
+        .with_input_nodes(2)
+        .with_output_nodes(1)
+        .set_fitness_evaluator(0.)
+        .build();
+    RealTimeEvolution::new(evolution, config)
+}
+
+#[test]
+fn replaces_worst_eligible() -> () {
+    let mut real_time = real_time(RealTimeConfig { replacement_interval: 5, minimum_age: 8, crossover_probability: 0.5 });
+    let mut replacements = Vec::new();
+    for tick in 1..=20 {
+        /* The simulation scores each network by its slot */
+        for species in 0..3 {
+            for index in 0..4 {
+                let output = real_time.network_mut(species, index).calculate_output(vec![1., 0.]);
+                assert!(output.len() == 1);
+                real_time.report_fitness(species, index, (species * 4 + index) as f32);
+            }
+        }
+
+        if let Some(replacement) = real_time.tick() { replacements.push((tick, replacement)); }
+    }
+
+    /* Nothing is old enough at tick 5 */
+    assert!(replacements.len() == 3 && replacements.iter().map(|(tick, _)| *tick).eq([10, 15, 20]));
+    /* The worst of whichever species was picked */
+    assert!(replacements[0].1.index == 0);
+    let last = replacements[2].1;
+    assert!(real_time.network(last.species, last.index).age() == 0 && real_time.network(2, 3).age() == 20);
+
+    /* Offspring are too young to be replaced right away */
+    assert!(replacements[1].1 != replacements[0].1);
+    assert!(real_time.ticks() == 20);
+}
+
+#[test]
+fn too_young() -> () {
+    let mut real_time = real_time(RealTimeConfig { replacement_interval: 1, minimum_age: 1000, ..Default::default() });
+    for _ in 0..10 { assert!(real_time.tick().is_none()); }
+    assert!(real_time.replace_worst().is_none());
+}
+
+#[test]
+fn replaces_within_parent_species() -> () {
+    let mut real_time = real_time(RealTimeConfig { replacement_interval: 1, minimum_age: 0, crossover_probability: 1.0 });
+
+    /* Only the last species has any fitness, its output nodes are slow */
+    for species in 0..3 {
+        for index in 0..4 {
+            real_time.report_fitness(species, index, if species == 2 { 1. + index as f32 } else { 0. });
+            real_time.network_mut(species, index).set_time_constant(2, 3.);
+        }
+    }
+
+    let replacement = real_time.tick().unwrap();
+    assert!(replacement == Replacement { species: 2, index: 0 });
+    assert!(real_time.network(2, 0).node_genes()[2].time_constant() == 3.);
+}
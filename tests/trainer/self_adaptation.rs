@@ -0,0 +1,69 @@
+use std::sync::Arc;
+use neat_algorithm::{neural_network::{activation::NetworkActivations, network::NeatNetwork}, trainer::{config::{network_config::NetworkConfig, self_adaptation::{Adaptation, MutationRates, SelfAdaptationConfig}}, evolution::Evolution}};
+
+fn network(adaptation: Option<SelfAdaptationConfig>) -> NeatNetwork {
+    let mut config = NetworkConfig::default();
+    config.self_adaptation = adaptation;
+    NeatNetwork::new(3, 2, Arc::default(), Arc::default(), NetworkActivations::default(), Arc::new(config))
+}
+
+#[test]
+fn log_normal() -> () {
+    let config = SelfAdaptationConfig { min_rate: 0.5, max_rate: 2.0, ..Default::default() };
+    let mut net = network(Some(config));
+    for _ in 0..100 { net.mutate(); }
+
+    let rates = net.mutation_rates();
+    assert!(rates != MutationRates::default());
+    for rate in [rates.split_connection, rates.create_connection, rates.change_weight, rates.step_size] {
+        assert!((0.5..=2.0).contains(&rate));
+    }
+    assert!(net.clone().mutation_rates() == rates);
+}
+
+#[test]
+fn fixed_without_config() -> () {
+    let mut net = network(None);
+    for _ in 0..100 { net.mutate(); }
+    assert!(net.mutation_rates() == MutationRates::default());
+}
+
+#[test]
+fn crossover_inherits() -> () {
+    let mut fit = network(None);
+    let weak = fit.clone();
+    fit.set_mutation_rates(MutationRates::uniform(3.0));
+    assert!(fit.crossover(&weak, 2.0, 1.0).mutation_rates() == MutationRates::uniform(3.0));
+    assert!(weak.crossover(&fit, 1.0, 2.0).mutation_rates() == MutationRates::uniform(3.0));
+    assert!(fit.crossover(&weak, 1.0, 2.0).mutation_rates() == MutationRates::default());
+}
+
+#[test]
+fn disabled_by_rate() -> () {
+    let mut net = network(None);
+    let mut rates = MutationRates::uniform(0.0);
+    rates.change_weight = 1.0;
+    net.set_mutation_rates(rates);
+    for _ in 0..200 { net.mutate(); }
+    assert!(net.node_genes().len() == 6);
+    assert!(net.get_genes().len() == 8);
+}
+
+#[test]
+fn one_fifth_rule() -> () {
+    let config = SelfAdaptationConfig { adaptation: Adaptation::OneFifthRule { factor: 0.5 }, ..Default::default() };
+    let mut evolution = Evolution::new()
+        .batch_size(2)
+        .with_species_size(4)
+        .with_input_nodes(2)
+        .with_output_nodes(1)
+        .self_adaptation(Some(config))
+        .set_fitness_evaluator(1.)
+        .build();
+
+    /* Constant fitness, no mutation ever helps */
+    for _ in 0..4 { evolution.generation(); }
+    assert!(evolution.mutation_scale() < 1.0);
+    let rates = evolution.species()[0].networks()[0].mutation_rates();
+    assert!(rates == MutationRates::uniform(evolution.mutation_scale()));
+}